@@ -0,0 +1,148 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{self, Mint, MintTo, Token, TokenAccount};
+use mpl_token_metadata::instructions::{CreateMasterEditionV3CpiBuilder, CreateMetadataAccountV3CpiBuilder};
+use mpl_token_metadata::types::DataV2;
+use crate::state::AiAgent;
+use crate::events::AgentUpdated;
+
+// Symbol of agent ownership NFTs
+pub const AGENT_NFT_SYMBOL: &str = "AGENT";
+// Maximum length of the off-chain metadata URI (Metaplex limit)
+pub const MAX_URI_LENGTH: usize = 200;
+
+// Mint a 1/1 NFT that carries the agent's metadata and from then on controls the agent.
+// Meant to be sent in the same transaction as `register_ai_agent` when the owner opts in.
+#[derive(Accounts)]
+pub struct MintAgentNft<'info> {
+    #[account(
+        mut,
+        seeds = [b"ai-agent", owner.key().as_ref(), &ai_agent.agent_id.to_le_bytes()],
+        bump = ai_agent.bump,
+        has_one = owner @ AgentNftError::Unauthorized,
+        constraint = !ai_agent.is_tokenized() @ AgentNftError::AlreadyTokenized
+    )]
+    pub ai_agent: Account<'info, AiAgent>,
+    #[account(
+        init,
+        payer = owner,
+        seeds = [b"agent-mint", ai_agent.key().as_ref()],
+        bump,
+        mint::decimals = 0,
+        mint::authority = ai_agent,
+        mint::freeze_authority = ai_agent
+    )]
+    pub agent_mint: Account<'info, Mint>,
+    #[account(
+        init,
+        payer = owner,
+        associated_token::mint = agent_mint,
+        associated_token::authority = owner
+    )]
+    pub owner_nft_account: Account<'info, TokenAccount>,
+    /// CHECK: created and validated by the token metadata program
+    #[account(mut)]
+    pub metadata: UncheckedAccount<'info>,
+    /// CHECK: created and validated by the token metadata program
+    #[account(mut)]
+    pub master_edition: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    /// CHECK: address is checked against the token metadata program ID
+    #[account(address = mpl_token_metadata::ID)]
+    pub token_metadata_program: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+// Metaplex metadata of an agent's ownership NFT
+pub fn agent_nft_data(name: &str, uri: String) -> Result<DataV2> {
+    require!(uri.len() <= MAX_URI_LENGTH, AgentNftError::UriTooLong);
+    Ok(DataV2 {
+        name: name.to_string(),
+        symbol: AGENT_NFT_SYMBOL.to_string(),
+        uri,
+        seller_fee_basis_points: 0,
+        creators: None,
+        collection: None,
+        uses: None,
+    })
+}
+
+pub fn mint_agent_nft(ctx: Context<MintAgentNft>, uri: String) -> Result<()> {
+    let clock = Clock::get()?;
+
+    let data = agent_nft_data(&ctx.accounts.ai_agent.name, uri)?;
+
+    // The agent PDA is mint, metadata update and edition authority
+    let ai_agent = &ctx.accounts.ai_agent;
+    let agent_id_bytes = ai_agent.agent_id.to_le_bytes();
+    let seeds = &[b"ai-agent".as_ref(), ai_agent.owner.as_ref(), agent_id_bytes.as_ref(), &[ai_agent.bump]];
+    let signer = &[&seeds[..]];
+    let agent_info = ai_agent.to_account_info();
+    let mint_info = ctx.accounts.agent_mint.to_account_info();
+    let owner_info = ctx.accounts.owner.to_account_info();
+    let metadata_info = ctx.accounts.metadata.to_account_info();
+    let system_info = ctx.accounts.system_program.to_account_info();
+    let rent_info = ctx.accounts.rent.to_account_info();
+
+    // Mint the single token to the owner
+    let cpi_accounts = MintTo {
+        mint: mint_info.clone(),
+        to: ctx.accounts.owner_nft_account.to_account_info(),
+        authority: agent_info.clone(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+    token::mint_to(cpi_ctx, 1)?;
+
+    CreateMetadataAccountV3CpiBuilder::new(&ctx.accounts.token_metadata_program)
+        .metadata(&metadata_info)
+        .mint(&mint_info)
+        .mint_authority(&agent_info)
+        .payer(&owner_info)
+        .update_authority(&agent_info, true)
+        .system_program(&system_info)
+        .data(data)
+        .is_mutable(true)
+        .invoke_signed(signer)?;
+
+    // A master edition with zero max supply fixes the supply at one
+    CreateMasterEditionV3CpiBuilder::new(&ctx.accounts.token_metadata_program)
+        .edition(&ctx.accounts.master_edition)
+        .mint(&mint_info)
+        .update_authority(&agent_info)
+        .mint_authority(&agent_info)
+        .payer(&owner_info)
+        .metadata(&metadata_info)
+        .token_program(&ctx.accounts.token_program)
+        .system_program(&system_info)
+        .rent(Some(&rent_info))
+        .max_supply(0)
+        .invoke_signed(signer)?;
+
+    let ai_agent = &mut ctx.accounts.ai_agent;
+    ai_agent.ownership_mint = ctx.accounts.agent_mint.key();
+
+    emit!(AgentUpdated {
+        agent_id: ai_agent.agent_id,
+        owner: ai_agent.owner,
+        timestamp: clock.unix_timestamp,
+        new_metadata: format!("ownership_mint={}", ai_agent.ownership_mint),
+    });
+
+    msg!("AI Agent {} tokenized as mint {}", ai_agent.agent_id, ai_agent.ownership_mint);
+    Ok(())
+}
+
+#[error_code]
+pub enum AgentNftError {
+    #[msg("Unauthorized access.")]
+    Unauthorized,
+    #[msg("Agent is already tokenized.")]
+    AlreadyTokenized,
+    #[msg("Metadata URI is too long.")]
+    UriTooLong,
+}
@@ -0,0 +1,396 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::keccak;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use crate::state::PlatformConfig;
+use crate::events::{DistributorClawedBack, DistributorCreated, MerkleRewardClaimed};
+
+// Maximum number of claims a single distributor can track in its bitmap
+pub const MAX_DISTRIBUTOR_CLAIMS: u64 = 8_192;
+// Maximum proof length accepted by `claim_merkle_reward` (enough for MAX_DISTRIBUTOR_CLAIMS leaves)
+pub const MAX_PROOF_LENGTH: usize = 13;
+
+// Domain separators so an inner node can never be passed off as a leaf
+const LEAF_PREFIX: &[u8] = &[0];
+const NODE_PREFIX: &[u8] = &[1];
+
+// Hash of a single (index, claimant, amount) entry
+pub fn hash_leaf(index: u64, claimant: &Pubkey, amount: u64) -> [u8; 32] {
+    keccak::hashv(&[LEAF_PREFIX, &index.to_le_bytes(), claimant.as_ref(), &amount.to_le_bytes()]).to_bytes()
+}
+
+// Hash of two child nodes; the pair is sorted so proofs do not need to carry left/right flags
+fn hash_pair(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (first, second) = if a <= b { (a, b) } else { (b, a) };
+    keccak::hashv(&[NODE_PREFIX, first, second]).to_bytes()
+}
+
+// Check that `leaf` is included in the tree with the given root
+pub fn verify_proof(proof: &[[u8; 32]], root: &[u8; 32], leaf: [u8; 32]) -> bool {
+    let computed = proof.iter().fold(leaf, |node, sibling| hash_pair(&node, sibling));
+    computed == *root
+}
+
+// Off-chain helper that builds the tree over hashed leaves and produces proofs for `claim_merkle_reward`
+pub struct MerkleTree {
+    // layers[0] holds the leaves, the last layer holds the root
+    layers: Vec<Vec<[u8; 32]>>,
+}
+
+impl MerkleTree {
+    // Build the tree from (claimant, amount) entries; the position in `entries` is the claim index
+    pub fn from_entries(entries: &[(Pubkey, u64)]) -> Self {
+        let leaves = entries
+            .iter()
+            .enumerate()
+            .map(|(index, (claimant, amount))| hash_leaf(index as u64, claimant, *amount))
+            .collect();
+        Self::new(leaves)
+    }
+
+    // Build the tree from already hashed leaves. A node without a sibling is promoted unchanged.
+    pub fn new(leaves: Vec<[u8; 32]>) -> Self {
+        let mut layers = vec![leaves];
+        while layers.last().map_or(false, |layer| layer.len() > 1) {
+            let next = layers
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => hash_pair(left, right),
+                    [single] => *single,
+                    _ => unreachable!(),
+                })
+                .collect();
+            layers.push(next);
+        }
+        Self { layers }
+    }
+
+    // Root to publish on-chain (all zeroes for an empty tree)
+    pub fn root(&self) -> [u8; 32] {
+        self.layers.last().and_then(|layer| layer.first()).copied().unwrap_or([0; 32])
+    }
+
+    // Sibling hashes from the leaf at `index` up to the root
+    pub fn proof(&self, index: usize) -> Option<Vec<[u8; 32]>> {
+        if index >= self.layers[0].len() {
+            return None;
+        }
+        let mut proof = Vec::new();
+        let mut position = index;
+        for layer in &self.layers[..self.layers.len() - 1] {
+            if let Some(sibling) = layer.get(position ^ 1) {
+                proof.push(*sibling);
+            }
+            position /= 2;
+        }
+        Some(proof)
+    }
+}
+
+// Holds the merkle root and funds of one epoch's off-chain computed payouts
+#[account]
+pub struct MerkleDistributor {
+    // Epoch the payouts belong to
+    pub epoch: u64,
+    // Root of the (index, claimant, amount) tree
+    pub root: [u8; 32],
+    // Mint the payouts are made in
+    pub mint: Pubkey,
+    // Sum of all amounts in the tree
+    pub total_amount: u64,
+    // Amount claimed so far
+    pub total_claimed: u64,
+    // Number of leaves in the tree
+    pub num_claims: u64,
+    // Timestamp after which unclaimed funds can be clawed back
+    pub expires_at: i64,
+    // Whether the unclaimed remainder was already clawed back
+    pub clawed_back: bool,
+    // Bump seed of the distributor vault PDA
+    pub vault_bump: u8,
+    // Bump seed for PDA derivation
+    pub bump: u8,
+    // One bit per leaf index, set once claimed
+    pub claimed: Vec<u8>,
+}
+
+impl MerkleDistributor {
+    pub fn space(num_claims: u64) -> usize {
+        8 + // discriminator
+        8 + // epoch (u64)
+        32 + // root ([u8; 32])
+        32 + // mint (Pubkey)
+        8 + // total_amount (u64)
+        8 + // total_claimed (u64)
+        8 + // num_claims (u64)
+        8 + // expires_at (i64)
+        1 + // clawed_back (bool)
+        1 + // vault_bump (u8)
+        1 + // bump (u8)
+        4 + Self::bitmap_len(num_claims) // claimed (Vec<u8>)
+    }
+
+    fn bitmap_len(num_claims: u64) -> usize {
+        ((num_claims + 7) / 8) as usize
+    }
+
+    pub fn is_claimed(&self, index: u64) -> bool {
+        self.claimed[(index / 8) as usize] & (1 << (index % 8)) != 0
+    }
+
+    fn set_claimed(&mut self, index: u64) {
+        self.claimed[(index / 8) as usize] |= 1 << (index % 8);
+    }
+}
+
+// Publish an epoch's merkle root and fund its vault (admin only)
+#[derive(Accounts)]
+#[instruction(epoch: u64, root: [u8; 32], total_amount: u64, num_claims: u64)]
+pub struct CreateDistributor<'info> {
+    #[account(
+        seeds = [b"platform-config"],
+        bump = platform_config.bump,
+        has_one = admin @ DistributorError::Unauthorized
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+    #[account(
+        init,
+        payer = admin,
+        space = MerkleDistributor::space(num_claims),
+        seeds = [b"merkle-distributor", &epoch.to_le_bytes()],
+        bump
+    )]
+    pub distributor: Account<'info, MerkleDistributor>,
+    pub mint: Account<'info, Mint>,
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"distributor-vault", distributor.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = distributor
+    )]
+    pub distributor_vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        constraint = admin_token_account.mint == mint.key() @ DistributorError::InvalidMint
+    )]
+    pub admin_token_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_distributor(
+    ctx: Context<CreateDistributor>,
+    epoch: u64,
+    root: [u8; 32],
+    total_amount: u64,
+    num_claims: u64,
+    expires_at: i64,
+) -> Result<()> {
+    let clock = Clock::get()?;
+
+    require!(total_amount > 0, DistributorError::InvalidAmount);
+    require!(num_claims > 0 && num_claims <= MAX_DISTRIBUTOR_CLAIMS, DistributorError::InvalidClaimCount);
+    require!(expires_at > clock.unix_timestamp, DistributorError::InvalidExpiry);
+
+    let distributor = &mut ctx.accounts.distributor;
+    distributor.epoch = epoch;
+    distributor.root = root;
+    distributor.mint = ctx.accounts.mint.key();
+    distributor.total_amount = total_amount;
+    distributor.total_claimed = 0;
+    distributor.num_claims = num_claims;
+    distributor.expires_at = expires_at;
+    distributor.clawed_back = false;
+    distributor.vault_bump = ctx.bumps.distributor_vault;
+    distributor.bump = ctx.bumps.distributor;
+    distributor.claimed = vec![0; MerkleDistributor::bitmap_len(num_claims)];
+
+    // Fund the vault with the full payout up front
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.admin_token_account.to_account_info(),
+        to: ctx.accounts.distributor_vault.to_account_info(),
+        authority: ctx.accounts.admin.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+    token::transfer(cpi_ctx, total_amount)?;
+
+    emit!(DistributorCreated {
+        epoch,
+        root,
+        mint: distributor.mint,
+        total_amount,
+        num_claims,
+        expires_at,
+    });
+
+    msg!("Merkle distributor for epoch {} created with {} claims totalling {}", epoch, num_claims, total_amount);
+    Ok(())
+}
+
+// Claim one leaf of a distributor
+#[derive(Accounts)]
+pub struct ClaimMerkleReward<'info> {
+    #[account(
+        mut,
+        seeds = [b"merkle-distributor", &distributor.epoch.to_le_bytes()],
+        bump = distributor.bump
+    )]
+    pub distributor: Account<'info, MerkleDistributor>,
+    #[account(
+        mut,
+        seeds = [b"distributor-vault", distributor.key().as_ref()],
+        bump = distributor.vault_bump
+    )]
+    pub distributor_vault: Account<'info, TokenAccount>,
+    pub claimant: Signer<'info>,
+    #[account(
+        mut,
+        constraint = claimant_token_account.mint == distributor.mint @ DistributorError::InvalidMint
+    )]
+    pub claimant_token_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn claim_merkle_reward(
+    ctx: Context<ClaimMerkleReward>,
+    index: u64,
+    amount: u64,
+    proof: Vec<[u8; 32]>,
+) -> Result<()> {
+    let distributor = &mut ctx.accounts.distributor;
+    let claimant = ctx.accounts.claimant.key();
+    let clock = Clock::get()?;
+
+    require!(clock.unix_timestamp < distributor.expires_at, DistributorError::DistributorExpired);
+    require!(index < distributor.num_claims, DistributorError::InvalidProof);
+    require!(proof.len() <= MAX_PROOF_LENGTH, DistributorError::InvalidProof);
+    require!(!distributor.is_claimed(index), DistributorError::AlreadyClaimed);
+    require!(
+        verify_proof(&proof, &distributor.root, hash_leaf(index, &claimant, amount)),
+        DistributorError::InvalidProof
+    );
+
+    distributor.set_claimed(index);
+    distributor.total_claimed = distributor.total_claimed
+        .checked_add(amount)
+        .filter(|claimed| *claimed <= distributor.total_amount)
+        .ok_or(DistributorError::InvalidAmount)?;
+
+    // Pay out from the vault, signed by the distributor PDA
+    let epoch_bytes = distributor.epoch.to_le_bytes();
+    let seeds = &[b"merkle-distributor".as_ref(), epoch_bytes.as_ref(), &[distributor.bump]];
+    let signer = &[&seeds[..]];
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.distributor_vault.to_account_info(),
+        to: ctx.accounts.claimant_token_account.to_account_info(),
+        authority: distributor.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+    token::transfer(cpi_ctx, amount)?;
+
+    emit!(MerkleRewardClaimed {
+        epoch: distributor.epoch,
+        index,
+        claimant,
+        amount,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Claimant {} claimed {} from epoch {} distributor", claimant, amount, distributor.epoch);
+    Ok(())
+}
+
+// Return unclaimed funds after the distributor expired (admin only)
+#[derive(Accounts)]
+pub struct ClawbackDistributor<'info> {
+    #[account(
+        seeds = [b"platform-config"],
+        bump = platform_config.bump,
+        has_one = admin @ DistributorError::Unauthorized
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+    #[account(
+        mut,
+        seeds = [b"merkle-distributor", &distributor.epoch.to_le_bytes()],
+        bump = distributor.bump
+    )]
+    pub distributor: Account<'info, MerkleDistributor>,
+    #[account(
+        mut,
+        seeds = [b"distributor-vault", distributor.key().as_ref()],
+        bump = distributor.vault_bump
+    )]
+    pub distributor_vault: Account<'info, TokenAccount>,
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        constraint = clawback_token_account.mint == distributor.mint @ DistributorError::InvalidMint
+    )]
+    pub clawback_token_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn clawback_distributor(ctx: Context<ClawbackDistributor>) -> Result<()> {
+    let distributor = &mut ctx.accounts.distributor;
+    let clock = Clock::get()?;
+
+    require!(clock.unix_timestamp >= distributor.expires_at, DistributorError::DistributorNotExpired);
+    require!(!distributor.clawed_back, DistributorError::AlreadyClawedBack);
+
+    distributor.clawed_back = true;
+    let amount = ctx.accounts.distributor_vault.amount;
+
+    if amount > 0 {
+        let epoch_bytes = distributor.epoch.to_le_bytes();
+        let seeds = &[b"merkle-distributor".as_ref(), epoch_bytes.as_ref(), &[distributor.bump]];
+        let signer = &[&seeds[..]];
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.distributor_vault.to_account_info(),
+            to: ctx.accounts.clawback_token_account.to_account_info(),
+            authority: distributor.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+        token::transfer(cpi_ctx, amount)?;
+    }
+
+    emit!(DistributorClawedBack {
+        epoch: distributor.epoch,
+        amount,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Clawed back {} unclaimed from epoch {} distributor", amount, distributor.epoch);
+    Ok(())
+}
+
+#[error_code]
+pub enum DistributorError {
+    #[msg("Unauthorized access.")]
+    Unauthorized,
+    #[msg("Token account mint does not match the distributor mint.")]
+    InvalidMint,
+    #[msg("Invalid distribution amount.")]
+    InvalidAmount,
+    #[msg("Claim count must be between 1 and the distributor maximum.")]
+    InvalidClaimCount,
+    #[msg("Expiry must be in the future.")]
+    InvalidExpiry,
+    #[msg("Merkle proof is invalid.")]
+    InvalidProof,
+    #[msg("This claim was already made.")]
+    AlreadyClaimed,
+    #[msg("Distributor has expired.")]
+    DistributorExpired,
+    #[msg("Distributor has not expired yet.")]
+    DistributorNotExpired,
+    #[msg("Unclaimed funds were already clawed back.")]
+    AlreadyClawedBack,
+}
@@ -84,6 +84,18 @@ pub enum SoreinError {
     #[msg("Governance action is not allowed at this time.")]
     GovernanceActionNotAllowed = 404,
 
+    /// Error when governance is switched off in the platform configuration.
+    #[msg("Governance is disabled.")]
+    GovernanceDisabled = 405,
+
+    /// Error when the proposal has already been finalized.
+    #[msg("Proposal has already been finalized.")]
+    ProposalAlreadyFinalized = 406,
+
+    /// Error when the proposal is finalized before its voting period ends.
+    #[msg("Voting period has not ended yet.")]
+    VotingPeriodNotEnded = 407,
+
     /// Error when the platform configuration parameters are invalid.
     #[msg("Invalid platform configuration parameters.")]
     InvalidConfig = 500,
@@ -74,6 +74,20 @@ pub struct StakeWithdrawn {
     pub timestamp: i64,
 }
 
+#[event]
+pub struct UnbondingStarted {
+    /// The user who requested the unstake.
+    pub user: Pubkey,
+    /// The unique ID of the AI agent unstaked from.
+    pub agent_id: u64,
+    /// The sequence number of the unbonding ticket.
+    pub ticket_id: u64,
+    /// The amount entering unbonding (in lamports or token units).
+    pub amount: u64,
+    /// The timestamp from which the amount can be withdrawn.
+    pub release_time: i64,
+}
+
 #[event]
 pub struct RewardClaimed {
     /// The user who claimed the reward.
@@ -86,6 +100,44 @@ pub struct RewardClaimed {
     pub timestamp: i64,
 }
 
+#[event]
+pub struct SlotRewardClaimed {
+    /// The user who claimed the reward.
+    pub user: Pubkey,
+    /// The unique ID of the AI agent associated with the reward.
+    pub agent_id: u64,
+    /// The mint of the reward token paid out.
+    pub mint: Pubkey,
+    /// The amount of reward claimed (in token units).
+    pub reward_amount: u64,
+    /// The timestamp when the reward was claimed.
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RewardVaultFunded {
+    /// The account that deposited reward liquidity.
+    pub funder: Pubkey,
+    /// The mint of the deposited reward token.
+    pub mint: Pubkey,
+    /// The amount deposited (in token units).
+    pub amount: u64,
+    /// The timestamp when the vault was funded.
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CommissionClaimed {
+    /// The agent owner who claimed the commission.
+    pub owner: Pubkey,
+    /// The unique ID of the AI agent the commission was earned on.
+    pub agent_id: u64,
+    /// The amount of commission claimed (in lamports or token units).
+    pub amount: u64,
+    /// The timestamp when the commission was claimed.
+    pub timestamp: i64,
+}
+
 #[event]
 pub struct ProposalCreated {
     /// The unique ID of the governance proposal.
@@ -137,3 +189,209 @@ pub struct RewardDistributed {
     /// The number of eligible users or agents who received rewards.
     pub eligible_count: u64,
 }
+
+#[event]
+pub struct DistributorCreated {
+    /// The epoch the off-chain computed payouts belong to.
+    pub epoch: u64,
+    /// The merkle root of the (index, claimant, amount) entries.
+    pub root: [u8; 32],
+    /// The mint the payouts are made in.
+    pub mint: Pubkey,
+    /// The total amount funded into the distributor vault.
+    pub total_amount: u64,
+    /// The number of claims in the tree.
+    pub num_claims: u64,
+    /// The timestamp after which unclaimed funds can be clawed back.
+    pub expires_at: i64,
+}
+
+#[event]
+pub struct MerkleRewardClaimed {
+    /// The epoch of the distributor claimed from.
+    pub epoch: u64,
+    /// The leaf index of the claim.
+    pub index: u64,
+    /// The account that claimed.
+    pub claimant: Pubkey,
+    /// The amount claimed (in token units).
+    pub amount: u64,
+    /// The timestamp of the claim.
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DistributorClawedBack {
+    /// The epoch of the expired distributor.
+    pub epoch: u64,
+    /// The unclaimed amount returned (in token units).
+    pub amount: u64,
+    /// The timestamp of the clawback.
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VestingReleased {
+    /// The user whose vested rewards were released.
+    pub user: Pubkey,
+    /// The amount released (in token units).
+    pub amount: u64,
+    /// The timestamp of the release.
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VestingExited {
+    /// The user who exited vesting early.
+    pub user: Pubkey,
+    /// The amount paid out (in token units).
+    pub amount: u64,
+    /// The forfeited amount returned to the reward pool (in token units).
+    pub penalty: u64,
+    /// The timestamp of the exit.
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CommissionChanged {
+    /// The unique ID of the AI agent.
+    pub agent_id: u64,
+    /// The owner of the AI agent.
+    pub owner: Pubkey,
+    /// The new commission (in basis points).
+    pub commission_bps: u64,
+    /// The timestamp from which the new commission applies.
+    pub effective_at: i64,
+}
+
+#[event]
+pub struct RewardPayoutDegraded {
+    /// The epoch paid out at a reduced ratio.
+    pub epoch: u64,
+    /// The share of each reward covered by the pool (in basis points).
+    pub payout_ratio_bps: u64,
+    /// The rewards available in the pool when the epoch started.
+    pub available: u64,
+    /// The rewards needed to pay every active agent in full.
+    pub required: u64,
+    /// The timestamp when the epoch started being distributed.
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AiAgentEvolved {
+    /// The public key of the evolved AI agent.
+    pub agent: Pubkey,
+    /// The unique ID of the AI agent.
+    pub agent_id: u64,
+    /// The chapter or milestone of evolution.
+    pub chapter_id: u32,
+    /// Hash of the updated behavior after evolution.
+    pub behavior_hash: [u8; 32],
+    /// Hash of the model the agent runs after evolution.
+    pub model_hash: [u8; 32],
+    /// Version of the model the agent runs after evolution.
+    pub model_version: u16,
+    /// The timestamp of the evolution.
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AgentJailed {
+    /// The public key of the jailed AI agent.
+    pub agent: Pubkey,
+    /// The unique ID of the AI agent.
+    pub agent_id: u64,
+    /// The timestamp of the agent's last heartbeat.
+    pub last_heartbeat_timestamp: i64,
+    /// The timestamp the agent was jailed.
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AgentUnjailed {
+    /// The public key of the unjailed AI agent.
+    pub agent: Pubkey,
+    /// The unique ID of the AI agent.
+    pub agent_id: u64,
+    /// The timestamp the agent was unjailed.
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SlashProposed {
+    /// The public key of the AI agent to be slashed.
+    pub agent: Pubkey,
+    /// The sequence number of the slash proposal on the agent.
+    pub slash_id: u32,
+    /// The admin or evaluator that proposed the slash.
+    pub proposer: Pubkey,
+    /// The share of each position to be slashed (in basis points).
+    pub slash_bps: u64,
+    /// Hash of the off-chain evidence.
+    pub reason_hash: [u8; 32],
+    /// The owner can appeal until this timestamp.
+    pub dispute_deadline: i64,
+}
+
+#[event]
+pub struct SlashAppealed {
+    /// The public key of the AI agent.
+    pub agent: Pubkey,
+    /// The sequence number of the slash proposal on the agent.
+    pub slash_id: u32,
+    /// The timestamp of the appeal.
+    pub timestamp: i64,
+    /// Governance must decide the appeal by this timestamp or the slash lapses.
+    pub resolution_deadline: i64,
+}
+
+#[event]
+pub struct SlashCancelled {
+    /// The public key of the AI agent.
+    pub agent: Pubkey,
+    /// The sequence number of the slash proposal on the agent.
+    pub slash_id: u32,
+    /// The timestamp the slash was dropped.
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SlashFinalized {
+    /// The public key of the slashed AI agent.
+    pub agent: Pubkey,
+    /// The sequence number of the slash proposal on the agent.
+    pub slash_id: u32,
+    /// The number of open positions and unbonding tickets the slash applies to.
+    pub positions: u64,
+    /// The timestamp the slash became final.
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SlashApplied {
+    /// The public key of the slashed AI agent.
+    pub agent: Pubkey,
+    /// The sequence number of the slash proposal on the agent.
+    pub slash_id: u32,
+    /// The number of positions slashed in this batch.
+    pub positions: u64,
+    /// The stake moved to the treasury in this batch.
+    pub amount: u64,
+    /// The number of positions still to be slashed.
+    pub positions_remaining: u64,
+}
+
+#[event]
+pub struct StakeRedelegated {
+    /// The public key of the user moving the stake.
+    pub user: Pubkey,
+    /// The ID of the AI agent the stake left.
+    pub from_agent_id: u64,
+    /// The ID of the AI agent the stake moved to.
+    pub to_agent_id: u64,
+    /// The amount of tokens moved.
+    pub amount: u64,
+    /// The moved stake stays exposed to slashes on the source agent until this timestamp.
+    pub exposure_end: i64,
+}
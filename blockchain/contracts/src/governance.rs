@@ -1,22 +1,25 @@
 use anchor_lang::prelude::*;
-use crate::state::{PlatformConfig, Proposal, UserStake};
+use crate::state::{
+    position_voting_power, PlatformConfig, Proposal, VoteRecord, MAX_PROPOSAL_DESCRIPTION_LENGTH,
+    MAX_PROPOSAL_TITLE_LENGTH, MAX_VOTE_OPTIONS, MAX_VOTE_OPTION_LENGTH,
+};
 use crate::events::{ProposalCreated, VoteCast, ProposalFinalized};
-use crate::error::HalnetError;
+use crate::error::SoreinError;
 
 /// Context for creating a new governance proposal.
 #[derive(Accounts)]
-pub struct CreateProposal<'info> {
+pub struct CreateGovernanceProposal<'info> {
     /// The creator of the proposal, must have staked tokens to propose.
     #[account(mut)]
     pub creator: Signer<'info>,
-    /// The platform configuration account to ensure governance is enabled.
-    #[account(has_one = authority @ HalnetError::UnauthorizedAccess)]
+    /// The platform configuration account to ensure governance is enabled; it also numbers the proposals.
+    #[account(mut, seeds = [b"platform-config"], bump = platform_config.bump)]
     pub platform_config: Account<'info, PlatformConfig>,
     /// The proposal account to be initialized.
     #[account(
         init,
         payer = creator,
-        space = Proposal::LEN,
+        space = Proposal::SPACE,
         seeds = [b"proposal", platform_config.proposal_count.to_le_bytes().as_ref()],
         bump
     )]
@@ -25,12 +28,12 @@ pub struct CreateProposal<'info> {
     pub system_program: Program<'info, System>,
 }
 
-impl<'info> CreateProposal<'info> {
+impl<'info> CreateGovernanceProposal<'info> {
     /// Validates that the creator has sufficient stake to create a proposal.
     pub fn validate(&self) -> Result<()> {
         // Check if governance is enabled in platform config.
         if !self.platform_config.governance_enabled {
-            return err!(FabeonError::GovernanceDisabled);
+            return err!(SoreinError::GovernanceDisabled);
         }
         // Placeholder for stake check (assumes a separate stake account or logic).
         // In a real implementation, check if creator has staked tokens.
@@ -40,7 +43,7 @@ impl<'info> CreateProposal<'info> {
 
 /// Instruction to create a new governance proposal.
 pub fn create_proposal(
-    ctx: Context<CreateProposal>,
+    ctx: Context<CreateGovernanceProposal>,
     title: String,
     description: String,
     voting_duration: u64,
@@ -50,14 +53,21 @@ pub fn create_proposal(
     ctx.accounts.validate()?;
 
     // Ensure the title and description are within size limits.
-    if title.len() > 100 || description.len() > 1000 {
-        return err!(RexoulError::InvalidInput);
+    if title.len() > MAX_PROPOSAL_TITLE_LENGTH || description.len() > MAX_PROPOSAL_DESCRIPTION_LENGTH {
+        return err!(SoreinError::InvalidProposalParameters);
     }
-    if options.len() < 2 || options.len() > 10 {
-        return err!(RexoulError::InvalidVoteOptions);
+    if options.len() < 2
+        || options.len() > MAX_VOTE_OPTIONS
+        || options.iter().any(|option| option.len() > MAX_VOTE_OPTION_LENGTH)
+    {
+        return err!(SoreinError::InvalidProposalParameters);
     }
 
     let clock = Clock::get()?;
+    let end_time = i64::try_from(voting_duration)
+        .ok()
+        .and_then(|duration| clock.unix_timestamp.checked_add(duration))
+        .ok_or(SoreinError::InvalidProposalParameters)?;
     let proposal = &mut ctx.accounts.proposal;
     let platform_config = &mut ctx.accounts.platform_config;
 
@@ -69,12 +79,15 @@ pub fn create_proposal(
     proposal.options = options.clone();
     proposal.votes = vec![0; options.len()];
     proposal.start_time = clock.unix_timestamp;
-    proposal.end_time = clock.unix_timestamp + voting_duration as i64;
+    proposal.end_time = end_time;
     proposal.status = 0; // 0 = Active
-    proposal.bump = *ctx.bumps.get("proposal").unwrap();
+    proposal.bump = ctx.bumps.proposal;
 
     // Increment the proposal counter in platform config.
-    platform_config.proposal_count += 1;
+    platform_config.proposal_count = platform_config
+        .proposal_count
+        .checked_add(1)
+        .ok_or(SoreinError::ArithmeticError)?;
 
     // Emit an event for proposal creation.
     emit!(ProposalCreated {
@@ -95,14 +108,20 @@ pub struct CastVote<'info> {
     #[account(mut)]
     pub voter: Signer<'info>,
     /// The platform configuration account to ensure governance is enabled.
-    #[account(has_one = authority @ RexoulError::UnauthorizedAccess)]
+    #[account(seeds = [b"platform-config"], bump = platform_config.bump)]
     pub platform_config: Account<'info, PlatformConfig>,
     /// The proposal account to vote on.
     #[account(mut, seeds = [b"proposal", proposal.id.to_le_bytes().as_ref()], bump = proposal.bump)]
     pub proposal: Account<'info, Proposal>,
-    /// The user's stake account to determine voting power (optional placeholder).
-    #[account(mut)]
-    pub user_stake: Option<Account<'info, UserStake>>,
+    /// The voter's vote on this proposal; it can only be created once, so nobody votes twice.
+    #[account(
+        init,
+        payer = voter,
+        space = VoteRecord::SPACE,
+        seeds = [b"vote-record", proposal.key().as_ref(), voter.key().as_ref()],
+        bump
+    )]
+    pub vote_record: Account<'info, VoteRecord>,
     /// The system program for account operations.
     pub system_program: Program<'info, System>,
 }
@@ -112,22 +131,21 @@ impl<'info> CastVote<'info> {
     pub fn validate(&self) -> Result<()> { 
         // Check if governance is enabled.
         if !self.platform_config.governance_enabled {
-            return err!(RexoulError::GovernanceDisabled);
+            return err!(SoreinError::GovernanceDisabled);
         }
         // Check if the proposal is active.
         let clock = Clock::get()?;
         if self.proposal.status != 0 || clock.unix_timestamp < self.proposal.start_time || clock.unix_timestamp > self.proposal.end_time {
-            return err!(RexoulError::ProposalNotActive);
+            return err!(SoreinError::InvalidProposal);
         }
-        // Placeholder for checking if the voter has already voted.
-        // In a real implementation, track votes per user to prevent double voting.
         Ok(())
     }
 }
 
 /// Instruction to cast a vote on a proposal.
-pub fn cast_vote(
-    ctx: Context<CastVote>,
+/// The voter's `StakePosition` accounts are passed as remaining accounts to determine voting weight.
+pub fn cast_vote<'info>(
+    ctx: Context<'_, '_, '_, 'info, CastVote<'info>>,
     proposal_id: u64,
     vote_option: u8,
 ) -> Result<()> {
@@ -137,20 +155,31 @@ pub fn cast_vote(
     let proposal = &mut ctx.accounts.proposal;
     // Ensure the proposal ID matches (redundant but for clarity).
     if proposal.id != proposal_id {
-        return err!(RexoulError::InvalidProposal);
+        return err!(SoreinError::InvalidProposal);
     }
     // Ensure the vote option is valid.
     if vote_option as usize >= proposal.options.len() {
-        return err!(RexoulError::InvalidVoteOption);
+        return err!(SoreinError::InvalidVote);
     }
 
     let clock = Clock::get()?;
-    // Calculate voting weight (placeholder logic).
-    // In a real implementation, derive weight from staked amount in user_stake.
-    let vote_weight = 1; // Simplified for now.
+    // Derive voting weight from the active stake of the voter's positions, including lock multipliers.
+    let vote_weight = position_voting_power(&ctx.accounts.voter.key(), ctx.remaining_accounts, clock.unix_timestamp)?;
+    if vote_weight == 0 {
+        return err!(SoreinError::InvalidVote);
+    }
 
-    // Record the vote.
-    proposal.votes[vote_option as usize] += vote_weight;
+    // Record the vote; the vote record's `init` already rejected a second vote by this voter.
+    let vote_record = &mut ctx.accounts.vote_record;
+    vote_record.init(
+        proposal.key(),
+        ctx.accounts.voter.key(),
+        vote_option,
+        vote_weight,
+        clock.unix_timestamp,
+        ctx.bumps.vote_record,
+    );
+    vote_record.tally(&mut proposal.votes)?;
 
     // Emit an event for vote casting.
     emit!(VoteCast {
@@ -158,7 +187,7 @@ pub fn cast_vote(
         voter: ctx.accounts.voter.key(),
         timestamp: clock.unix_timestamp,
         vote_option,
-        vote_weight: vote_weight as u64,
+        vote_weight,
     });
 
     Ok(())
@@ -171,7 +200,7 @@ pub struct FinalizeProposal<'info> {
     #[account(mut)]
     pub caller: Signer<'info>,
     /// The platform configuration account to ensure governance is enabled.
-    #[account(has_one = authority @ RexoulError::UnauthorizedAccess)]
+    #[account(seeds = [b"platform-config"], bump = platform_config.bump)]
     pub platform_config: Account<'info, PlatformConfig>,
     /// The proposal account to finalize.
     #[account(mut, seeds = [b"proposal", proposal.id.to_le_bytes().as_ref()], bump = proposal.bump)]
@@ -185,15 +214,15 @@ impl<'info> FinalizeProposal<'info> {
     pub fn validate(&self) -> Result<()> {
         // Check if governance is enabled.
         if !self.platform_config.governance_enabled {
-            return err!(RexoulError::GovernanceDisabled);
+            return err!(SoreinError::GovernanceDisabled);
         }
         // Check if the proposal is still active and voting period has ended.
         let clock = Clock::get()?;
         if self.proposal.status != 0 {
-            return err!(RexoulError::ProposalAlreadyFinalized);
+            return err!(SoreinError::ProposalAlreadyFinalized);
         }
         if clock.unix_timestamp <= self.proposal.end_time {
-            return err!(RexoulError::VotingPeriodNotEnded);
+            return err!(SoreinError::VotingPeriodNotEnded);
         }
         Ok(())
     }
@@ -210,7 +239,7 @@ pub fn finalize_proposal(
     let proposal = &mut ctx.accounts.proposal;
     // Ensure the proposal ID matches.
     if proposal.id != proposal_id {
-        return err!(RexoulError::InvalidProposal);
+        return err!(SoreinError::InvalidProposal);
     }
 
     let clock = Clock::get()?;
@@ -1,7 +1,8 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
 use crate::state::*;
-use crate::ErrorCode;
+use crate::vesting::VestingEscrow;
+use crate::events::{AgentJailed, AgentRegistered, AgentUnjailed, AgentUpdated, AiAgentEvolved, CommissionChanged, CommissionClaimed, RewardClaimed, RewardVaultFunded, SlotRewardClaimed, StakeDeposited, StakeRedelegated, StakeWithdrawn, UnbondingStarted};
 
 // Initialize the platform configuration
 #[derive(Accounts)]
@@ -21,18 +22,20 @@ pub struct InitializePlatform<'info> {
 
 pub fn initialize_platform(
     ctx: Context<InitializePlatform>,
-    reward_rate_bps: u64,
     min_stake_amount: u64,
     epoch_duration: i64,
+    unbonding_period: i64,
 ) -> Result<()> {
     let platform_config = &mut ctx.accounts.platform_config;
     let bump = ctx.bumps.platform_config;
 
+    require!(unbonding_period >= 0, ErrorCode::InvalidUnbondingPeriod);
+
     platform_config.init(
         ctx.accounts.admin.key(),
-        reward_rate_bps,
         min_stake_amount,
         epoch_duration,
+        unbonding_period,
         bump,
     );
 
@@ -50,72 +53,285 @@ pub struct UpdatePlatformConfig<'info> {
         has_one = admin @ ErrorCode::Unauthorized
     )]
     pub platform_config: Account<'info, PlatformConfig>,
+    #[account(
+        mut,
+        seeds = [b"emission-schedule"],
+        bump = emission_schedule.bump
+    )]
+    pub emission_schedule: Account<'info, EmissionSchedule>,
     #[account(mut)]
     pub admin: Signer<'info>,
 }
 
 pub fn update_platform_config(
     ctx: Context<UpdatePlatformConfig>,
-    reward_rate_bps: u64,
     min_stake_amount: u64,
     epoch_duration: i64,
+    unbonding_period: i64,
 ) -> Result<()> {
     let platform_config = &mut ctx.accounts.platform_config;
+    let clock = Clock::get()?;
+
+    require!(unbonding_period >= 0, ErrorCode::InvalidUnbondingPeriod);
+    // Emission epochs are counted from the schedule start in units of epoch_duration, so the
+    // length is fixed once emission has begun
+    require!(
+        epoch_duration == platform_config.epoch_duration
+            || clock.unix_timestamp < ctx.accounts.emission_schedule.start_timestamp,
+        ErrorCode::EpochDurationLocked
+    );
+
+    // Close out accrual under the old epoch length before changing it
+    platform_config.accrue_rewards_fully(&mut ctx.accounts.emission_schedule, clock.unix_timestamp)?;
 
-    platform_config.reward_rate_bps = reward_rate_bps;
     platform_config.min_stake_amount = min_stake_amount;
     platform_config.epoch_duration = epoch_duration;
+    platform_config.unbonding_period = unbonding_period;
 
     msg!("Platform config updated by admin: {}", ctx.accounts.admin.key());
     Ok(())
 }
 
-// Register a new AI agent
+// Set how emissions are split between stake and performance, and who scores agents (admin only)
+pub fn update_reward_distribution(
+    ctx: Context<UpdatePlatformConfig>,
+    stake_weight_bps: u64,
+    performance_weight_bps: u64,
+    evaluator: Pubkey,
+) -> Result<()> {
+    let platform_config = &mut ctx.accounts.platform_config;
+    let clock = Clock::get()?;
+
+    require!(
+        stake_weight_bps.checked_add(performance_weight_bps) == Some(10_000),
+        ErrorCode::InvalidRewardWeights
+    );
+
+    // Close out accrual under the old split before changing it
+    platform_config.accrue_rewards_fully(&mut ctx.accounts.emission_schedule, clock.unix_timestamp)?;
+
+    platform_config.stake_weight_bps = stake_weight_bps;
+    platform_config.performance_weight_bps = performance_weight_bps;
+    platform_config.evaluator = evaluator;
+
+    msg!(
+        "Reward distribution updated: stake {} bps, performance {} bps, evaluator {}",
+        stake_weight_bps,
+        performance_weight_bps,
+        evaluator
+    );
+    Ok(())
+}
+
+// Configure vesting of claimed rewards; a zero duration pays claims out immediately (admin only)
+pub fn update_vesting_config(
+    ctx: Context<UpdatePlatformConfig>,
+    vesting_cliff: i64,
+    vesting_duration: i64,
+    early_exit_penalty_bps: u64,
+) -> Result<()> {
+    let platform_config = &mut ctx.accounts.platform_config;
+
+    require!(
+        vesting_duration >= 0 && vesting_cliff >= 0 && vesting_cliff <= vesting_duration,
+        ErrorCode::InvalidVestingSchedule
+    );
+    require!(early_exit_penalty_bps <= 10_000, ErrorCode::InvalidVestingSchedule);
+
+    platform_config.vesting_cliff = vesting_cliff;
+    platform_config.vesting_duration = vesting_duration;
+    platform_config.early_exit_penalty_bps = early_exit_penalty_bps;
+
+    msg!(
+        "Vesting updated: cliff {}s, duration {}s, early exit penalty {} bps",
+        vesting_cliff,
+        vesting_duration,
+        early_exit_penalty_bps
+    );
+    Ok(())
+}
+
+// Set the governance cap on agent commission and the notice period for increases (admin only).
+// Lowering the cap clamps every agent's commission from its next settlement on; raising it is
+// announced under the current notice period and never raises an agent's commission by itself.
+pub fn update_commission_policy(
+    ctx: Context<UpdatePlatformConfig>,
+    max_commission_bps: u64,
+    commission_notice_period: i64,
+) -> Result<()> {
+    let platform_config = &mut ctx.accounts.platform_config;
+    let clock = Clock::get()?;
+
+    require!(max_commission_bps <= 10_000, ErrorCode::InvalidCommission);
+    require!(commission_notice_period >= 0, ErrorCode::InvalidCommission);
+
+    // Close out accrual under the old cap before changing it
+    platform_config.accrue_rewards_fully(&mut ctx.accounts.emission_schedule, clock.unix_timestamp)?;
+    platform_config.schedule_commission_cap(max_commission_bps, clock.unix_timestamp)?;
+    platform_config.commission_notice_period = commission_notice_period;
+
+    msg!(
+        "Commission policy updated: cap {} bps, notice period {}s",
+        max_commission_bps,
+        commission_notice_period
+    );
+    Ok(())
+}
+
+// Set the minimum interval between agent evolutions (admin only)
+pub fn update_evolution_interval(ctx: Context<UpdatePlatformConfig>, min_evolution_interval: i64) -> Result<()> {
+    require!(min_evolution_interval >= 0, ErrorCode::InvalidEvolutionInterval);
+
+    ctx.accounts.platform_config.min_evolution_interval = min_evolution_interval;
+
+    msg!("Minimum evolution interval set to {}s", min_evolution_interval);
+    Ok(())
+}
+
+// Set the agent liveness window and minimum jail duration (admin only)
+pub fn update_liveness_config(ctx: Context<UpdatePlatformConfig>, liveness_window: i64, min_jail_duration: i64) -> Result<()> {
+    require!(liveness_window >= 0 && min_jail_duration >= 0, ErrorCode::InvalidLivenessConfig);
+
+    let platform_config = &mut ctx.accounts.platform_config;
+    platform_config.liveness_window = liveness_window;
+    platform_config.min_jail_duration = min_jail_duration;
+
+    msg!("Liveness window set to {}s, minimum jail duration {}s", liveness_window, min_jail_duration);
+    Ok(())
+}
+
+// Set stake warmup and cooldown in epochs and the per-epoch activation and deactivation cap (admin only)
+pub fn update_activation_config(
+    ctx: Context<UpdatePlatformConfig>,
+    warmup_epochs: u64,
+    cooldown_epochs: u64,
+    activation_cap_bps: u64,
+) -> Result<()> {
+    require!(activation_cap_bps <= 10_000, ErrorCode::InvalidActivationConfig);
+
+    ctx.accounts.platform_config.set_activation_config(warmup_epochs, cooldown_epochs, activation_cap_bps);
+
+    msg!(
+        "Activation config updated: warmup {} epochs, cooldown {} epochs, cap {} bps",
+        warmup_epochs,
+        cooldown_epochs,
+        activation_cap_bps
+    );
+    Ok(())
+}
+
+// Set the minimum interval between redelegations of a position (admin only)
+pub fn update_redelegation_cooldown(ctx: Context<UpdatePlatformConfig>, redelegation_cooldown: i64) -> Result<()> {
+    require!(redelegation_cooldown >= 0, ErrorCode::InvalidRedelegationCooldown);
+
+    ctx.accounts.platform_config.redelegation_cooldown = redelegation_cooldown;
+
+    msg!("Redelegation cooldown set to {}s", redelegation_cooldown);
+    Ok(())
+}
+
+// Switch staker governance on or off (admin only)
+pub fn update_governance_config(ctx: Context<UpdatePlatformConfig>, governance_enabled: bool) -> Result<()> {
+    ctx.accounts.platform_config.governance_enabled = governance_enabled;
+
+    msg!("Governance {}", if governance_enabled { "enabled" } else { "disabled" });
+    Ok(())
+}
+
+// Set where slashed stake goes, the appeal and resolution windows and the per-slash cap (admin only)
+pub fn update_slashing_config(
+    ctx: Context<UpdatePlatformConfig>,
+    slash_treasury: Pubkey,
+    slash_dispute_window: i64,
+    slash_resolution_window: i64,
+    max_slash_bps: u64,
+) -> Result<()> {
+    require!(
+        slash_dispute_window >= 0 && slash_resolution_window >= 0 && max_slash_bps <= 10_000,
+        ErrorCode::InvalidSlashingConfig
+    );
+
+    let platform_config = &mut ctx.accounts.platform_config;
+    platform_config.slash_treasury = slash_treasury;
+    platform_config.slash_dispute_window = slash_dispute_window;
+    platform_config.slash_resolution_window = slash_resolution_window;
+    platform_config.max_slash_bps = max_slash_bps;
+
+    msg!(
+        "Slashing config updated: treasury {}, dispute window {}s, resolution window {}s, cap {} bps",
+        slash_treasury,
+        slash_dispute_window,
+        slash_resolution_window,
+        max_slash_bps
+    );
+    Ok(())
+}
+
+// Create the reward emission schedule (admin only)
 #[derive(Accounts)]
-pub struct RegisterAiAgent<'info> {
+pub struct InitializeEmissionSchedule<'info> {
+    #[account(
+        mut,
+        seeds = [b"platform-config"],
+        bump = platform_config.bump,
+        has_one = admin @ ErrorCode::Unauthorized
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
     #[account(
         init,
-        payer = owner,
-        space = AiAgent::SPACE,
-        seeds = [b"ai-agent", owner.key().as_ref(), &agent_id.to_le_bytes()],
+        payer = admin,
+        space = EmissionSchedule::SPACE,
+        seeds = [b"emission-schedule"],
         bump
     )]
-    pub ai_agent: Account<'info, AiAgent>,
+    pub emission_schedule: Account<'info, EmissionSchedule>,
     #[account(mut)]
-    pub owner: Signer<'info>,
+    pub admin: Signer<'info>,
     pub system_program: Program<'info, System>,
 }
 
-pub fn register_ai_agent(
-    ctx: Context<RegisterAiAgent>,
-    agent_id: u64,
-    name: String,
-    description: String,
+pub fn initialize_emission_schedule(
+    ctx: Context<InitializeEmissionSchedule>,
+    curve: EmissionCurve,
+    start_timestamp: i64,
+    supply_cap: u64,
+) -> Result<()> {
+    curve.validate()?;
+
+    ctx.accounts.emission_schedule.init(curve, start_timestamp, supply_cap, ctx.bumps.emission_schedule);
+    // Stake epochs (warmup, activation, cooldown) count from the same start as emission epochs
+    ctx.accounts.platform_config.epoch_origin = start_timestamp;
+
+    msg!("Emission schedule starting at {} with supply cap {}", start_timestamp, supply_cap);
+    Ok(())
+}
+
+// Replace the emission curve and supply cap (admin only)
+pub fn update_emission_schedule(
+    ctx: Context<UpdatePlatformConfig>,
+    curve: EmissionCurve,
+    supply_cap: u64,
 ) -> Result<()> {
-    let ai_agent = &mut ctx.accounts.ai_agent;
-    let bump = ctx.bumps.ai_agent;
     let clock = Clock::get()?;
 
-    // Validate input lengths
-    require!(name.len() <= MAX_NAME_LENGTH, ErrorCode::MetadataTooLarge);
-    require!(description.len() <= MAX_DESCRIPTION_LENGTH, ErrorCode::MetadataTooLarge);
+    curve.validate()?;
+    require!(supply_cap >= ctx.accounts.emission_schedule.total_emitted, ErrorCode::InvalidSupplyCap);
 
-    ai_agent.init(
-        agent_id,
-        ctx.accounts.owner.key(),
-        name,
-        description,
-        clock.unix_timestamp,
-        bump,
-    );
+    // Close out accrual under the old curve before changing it
+    ctx.accounts.platform_config.accrue_rewards_fully(&mut ctx.accounts.emission_schedule, clock.unix_timestamp)?;
 
-    msg!("AI Agent registered: ID {} by owner {}", agent_id, ctx.accounts.owner.key());
+    let emission_schedule = &mut ctx.accounts.emission_schedule;
+    emission_schedule.curve = curve;
+    emission_schedule.supply_cap = supply_cap;
+
+    msg!("Emission schedule updated by admin: {}", ctx.accounts.admin.key());
     Ok(())
 }
 
-// Stake tokens on an AI agent
+// Accrue emissions up to now, at most MAX_ACCRUAL_EPOCHS per call (permissionless). Lets anyone
+// work through a long accrual backlog so weight and config changes can go ahead.
 #[derive(Accounts)]
-pub struct StakeOnAgent<'info> {
+pub struct AccrueRewards<'info> {
     #[account(
         mut,
         seeds = [b"platform-config"],
@@ -124,192 +340,1922 @@ pub struct StakeOnAgent<'info> {
     pub platform_config: Account<'info, PlatformConfig>,
     #[account(
         mut,
-        seeds = [b"ai-agent", user.key().as_ref(), &agent_id.to_le_bytes()],
-        bump = ai_agent.bump,
-        has_one = owner @ ErrorCode::Unauthorized
+        seeds = [b"emission-schedule"],
+        bump = emission_schedule.bump
     )]
-    pub ai_agent: Account<'info, AiAgent>,
+    pub emission_schedule: Account<'info, EmissionSchedule>,
+}
+
+pub fn accrue_rewards(ctx: Context<AccrueRewards>) -> Result<()> {
+    let platform_config = &mut ctx.accounts.platform_config;
+    let clock = Clock::get()?;
+
+    platform_config.accrue_rewards(&mut ctx.accounts.emission_schedule, clock.unix_timestamp)?;
+
+    msg!("Rewards accrued up to {}", platform_config.last_reward_timestamp);
+    Ok(())
+}
+
+// Create the program-owned reward vault (admin only)
+#[derive(Accounts)]
+pub struct InitializeRewardVault<'info> {
     #[account(
-        init_if_needed,
-        payer = user,
-        space = UserStake::SPACE,
-        seeds = [b"user-stake", user.key().as_ref()],
-        bump
+        mut,
+        seeds = [b"platform-config"],
+        bump = platform_config.bump,
+        has_one = admin @ ErrorCode::Unauthorized
     )]
-    pub user_stake: Account<'info, UserStake>,
-    #[account(mut)]
-    pub user: Signer<'info>,
-    #[account(mut)]
-    pub user_token_account: Account<'info, TokenAccount>,
+    pub platform_config: Account<'info, PlatformConfig>,
+    pub reward_mint: Account<'info, Mint>,
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"reward-vault"],
+        bump,
+        token::mint = reward_mint,
+        token::authority = platform_config
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
     #[account(mut)]
-    pub platform_vault: Account<'info, TokenAccount>,
+    pub admin: Signer<'info>,
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 }
 
-pub fn stake_on_agent(
-    ctx: Context<StakeOnAgent>,
-    agent_id: u64,
-    amount: u64,
-) -> Result<()> {
+pub fn initialize_reward_vault(ctx: Context<InitializeRewardVault>) -> Result<()> {
     let platform_config = &mut ctx.accounts.platform_config;
-    let ai_agent = &mut ctx.accounts.ai_agent;
-    let user_stake = &mut ctx.accounts.user_stake;
-    let clock = Clock::get()?;
-
-    // Validate stake amount
-    require!(amount >= platform_config.min_stake_amount, ErrorCode::InvalidStakeAmount);
 
-    // Initialize user stake if newly created
-    if user_stake.user == Pubkey::default() {
-        user_stake.init(ctx.accounts.user.key(), ctx.bumps.user_stake);
-    }
+    platform_config.reward_mint = ctx.accounts.reward_mint.key();
+    platform_config.reward_vault_bump = ctx.bumps.reward_vault;
 
-    // Add agent to user's staked agents list
-    user_stake.add_staked_agent(agent_id)?;
+    msg!("Reward vault {} created for mint {}", ctx.accounts.reward_vault.key(), platform_config.reward_mint);
+    Ok(())
+}
 
-    // Update stake amounts
-    user_stake.staked_amount = user_stake.staked_amount.checked_add(amount).ok_or(ErrorCode::InvalidStakeAmount)?;
-    ai_agent.staked_amount = ai_agent.staked_amount.checked_add(amount).ok_or(ErrorCode::InvalidStakeAmount)?;
-    platform_config.total_staked = platform_config.total_staked.checked_add(amount).ok_or(ErrorCode::InvalidStakeAmount)?;
+// Create the program-owned vault holding staked tokens (admin only)
+#[derive(Accounts)]
+pub struct InitializeStakeVault<'info> {
+    #[account(
+        mut,
+        seeds = [b"platform-config"],
+        bump = platform_config.bump,
+        has_one = admin @ ErrorCode::Unauthorized,
+        constraint = platform_config.stake_mint == Pubkey::default() @ ErrorCode::InvalidVault
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+    pub stake_mint: Account<'info, Mint>,
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"stake-vault"],
+        bump,
+        token::mint = stake_mint,
+        token::authority = platform_config
+    )]
+    pub stake_vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
 
-    // Update timestamps
-    user_stake.last_stake_update = clock.unix_timestamp;
+pub fn initialize_stake_vault(ctx: Context<InitializeStakeVault>) -> Result<()> {
+    let platform_config = &mut ctx.accounts.platform_config;
 
-    // Transfer tokens from user to platform vault
-    let cpi_accounts = Transfer {
-        from: ctx.accounts.user_token_account.to_account_info(),
-        to: ctx.accounts.platform_vault.to_account_info(),
-        authority: ctx.accounts.user.to_account_info(),
-    };
-    let cpi_program = ctx.accounts.token_program.to_account_info();
-    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-    token::transfer(cpi_ctx, amount)?;
+    platform_config.stake_mint = ctx.accounts.stake_mint.key();
+    platform_config.stake_vault_bump = ctx.bumps.stake_vault;
 
-    msg!("User {} staked {} on agent {}", ctx.accounts.user.key(), amount, agent_id);
+    msg!("Stake vault {} created for mint {}", ctx.accounts.stake_vault.key(), platform_config.stake_mint);
     Ok(())
 }
 
-// Claim accumulated rewards
+// Deposit reward liquidity into the reward vault
 #[derive(Accounts)]
-pub struct ClaimRewards<'info> {
+pub struct FundRewardVault<'info> {
     #[account(
-        mut,
         seeds = [b"platform-config"],
         bump = platform_config.bump
     )]
     pub platform_config: Account<'info, PlatformConfig>,
     #[account(
         mut,
-        seeds = [b"user-stake", user.key().as_ref()],
-        bump = user_stake.bump,
-        has_one = user @ ErrorCode::Unauthorized
+        seeds = [b"reward-vault"],
+        bump = platform_config.reward_vault_bump,
+        constraint = reward_vault.mint == platform_config.reward_mint @ ErrorCode::InvalidRewardMint
     )]
-    pub user_stake: Account<'info, UserStake>,
-    #[account(mut)]
-    pub user: Signer<'info>,
-    #[account(mut)]
-    pub user_token_account: Account<'info, TokenAccount>,
+    pub reward_vault: Account<'info, TokenAccount>,
     #[account(mut)]
-    pub platform_vault: Account<'info, TokenAccount>,
+    pub funder: Signer<'info>,
+    #[account(
+        mut,
+        constraint = funder_token_account.mint == platform_config.reward_mint @ ErrorCode::InvalidRewardMint
+    )]
+    pub funder_token_account: Account<'info, TokenAccount>,
     pub token_program: Program<'info, Token>,
 }
 
-pub fn claim_rewards(ctx: Context<ClaimRewards>) -> Result<()> {
-    let platform_config = &mut ctx.accounts.platform_config;
-    let user_stake = &mut ctx.accounts.user_stake;
+pub fn fund_reward_vault(ctx: Context<FundRewardVault>, amount: u64) -> Result<()> {
     let clock = Clock::get()?;
 
-    // Calculate elapsed epochs since last claim
-    let elapsed_time = clock.unix_timestamp - user_stake.last_reward_claim;
-    let elapsed_epochs = elapsed_time / platform_config.epoch_duration;
-    if elapsed_epochs <= 0 {
-        return err!(ErrorCode::NoRewardsToClaim);
-    }
-
-    // Calculate rewards based on staked amount and reward rate
-    let reward_per_epoch = (user_stake.staked_amount as u128)
-        .checked_mul(platform_config.reward_rate_bps as u128)
-        .ok_or(ErrorCode::InvalidStakeAmount)?
-        .checked_div(10000) // Convert basis points to percentage
-        .ok_or(ErrorCode::InvalidStakeAmount)? as u64;
-    let total_reward = reward_per_epoch
-        .checked_mul(elapsed_epochs as u64)
-        .ok_or(ErrorCode::InvalidStakeAmount)?;
+    require!(amount > 0, ErrorCode::InvalidStakeAmount);
 
-    // Update accumulated rewards and reset claim timestamp
-    user_stake.accumulated_rewards = user_stake.accumulated_rewards
-        .checked_add(total_reward)
-        .ok_or(ErrorCode::InvalidStakeAmount)?;
-    let reward_to_claim = user_stake.accumulated_rewards;
-    user_stake.accumulated_rewards = 0;
-    user_stake.last_reward_claim = clock.unix_timestamp;
-
-    // Transfer rewards from platform vault to user
     let cpi_accounts = Transfer {
-        from: ctx.accounts.platform_vault.to_account_info(),
-        to: ctx.accounts.user_token_account.to_account_info(),
-        authority: ctx.accounts.platform_vault.to_account_info(), // Assumes vault has delegated authority or program owns it
+        from: ctx.accounts.funder_token_account.to_account_info(),
+        to: ctx.accounts.reward_vault.to_account_info(),
+        authority: ctx.accounts.funder.to_account_info(),
     };
     let cpi_program = ctx.accounts.token_program.to_account_info();
     let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-    token::transfer(cpi_ctx, reward_to_claim)?;
+    token::transfer(cpi_ctx, amount)?;
 
-    msg!("User {} claimed rewards: {}", ctx.accounts.user.key(), reward_to_claim);
+    emit!(RewardVaultFunded {
+        funder: ctx.accounts.funder.key(),
+        mint: ctx.accounts.platform_config.reward_mint,
+        amount,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Reward vault funded with {} by {}", amount, ctx.accounts.funder.key());
     Ok(())
 }
 
-// Vote on governance proposals (e.g., update reward rates)
+// Add a partner reward mint with its own vault (admin only)
 #[derive(Accounts)]
-pub struct VoteOnProposal<'info> {
+pub struct AddRewardSlot<'info> {
     #[account(
         mut,
         seeds = [b"platform-config"],
-        bump = platform_config.bump
+        bump = platform_config.bump,
+        has_one = admin @ ErrorCode::Unauthorized
     )]
     pub platform_config: Account<'info, PlatformConfig>,
     #[account(
         mut,
-        seeds = [b"user-stake", voter.key().as_ref()],
-        bump = user_stake.bump,
-        has_one = voter @ ErrorCode::Unauthorized
+        seeds = [b"emission-schedule"],
+        bump = emission_schedule.bump
     )]
-    pub user_stake: Account<'info, UserStake>,
-    #[account(mut)]
-    pub voter: Signer<'info>,
+    pub emission_schedule: Account<'info, EmissionSchedule>,
+    pub reward_mint: Account<'info, Mint>,
     #[account(
-        init_if_needed,
-        payer = voter,
-        space = Metadata::SPACE,
-        seeds = [b"proposal-vote", &proposal_id.to_le_bytes(), voter.key().as_ref()],
-        bump
+        init,
+        payer = admin,
+        seeds = [b"reward-vault", reward_mint.key().as_ref()],
+        bump,
+        token::mint = reward_mint,
+        token::authority = platform_config
     )]
-    pub vote_record: Account<'info, Metadata>,
+    pub slot_vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 }
 
-pub fn vote_on_proposal(
-    ctx: Context<VoteOnProposal>,
-    proposal_id: u64,
-    in_favor: bool,
-) -> Result<()> {
-    let user_stake = &ctx.accounts.user_stake;
-    let vote_record = &mut ctx.accounts.vote_record;
+pub fn add_reward_slot(ctx: Context<AddRewardSlot>, rate_per_epoch: u64) -> Result<()> {
+    let platform_config = &mut ctx.accounts.platform_config;
+    let reward_mint = ctx.accounts.reward_mint.key();
     let clock = Clock::get()?;
 
-    // Ensure user has staked tokens to have voting power
-    require!(user_stake.staked_amount > 0, ErrorCode::InvalidStakeAmount);
+    require!(reward_mint != platform_config.reward_mint, ErrorCode::DuplicateRewardMint);
+    require!(
+        !platform_config.reward_slots.iter().any(|slot| slot.mint == reward_mint),
+        ErrorCode::DuplicateRewardMint
+    );
+
+    // Close out accrual so the new slot only earns from now on
+    platform_config.accrue_rewards_fully(&mut ctx.accounts.emission_schedule, clock.unix_timestamp)?;
 
-    // Record the vote (simplified as metadata)
-    let vote_data = format!("Vote: {}", if in_favor { "Yes" } else { "No" });
-    vote_record.init(
-        proposal_id,
-        vote_data,
-        clock.unix_timestamp,
-        ctx.bumps.vote_record,
+    let index = platform_config.reward_slots
+        .iter()
+        .position(|slot| !slot.is_active())
+        .ok_or(ErrorCode::RewardSlotsFull)?;
+    platform_config.reward_slots[index] = RewardSlot {
+        mint: reward_mint,
+        rate_per_epoch,
+        acc_reward_per_share: 0,
+        vault_bump: ctx.bumps.slot_vault,
+    };
+
+    msg!("Reward slot {} added for mint {} at {} per epoch", index, reward_mint, rate_per_epoch);
+    Ok(())
+}
+
+// Change the emission rate of a partner reward mint (admin only)
+pub fn update_reward_slot(ctx: Context<UpdatePlatformConfig>, index: u8, rate_per_epoch: u64) -> Result<()> {
+    let platform_config = &mut ctx.accounts.platform_config;
+    let clock = Clock::get()?;
+
+    require!(
+        platform_config.reward_slots.get(index as usize).map_or(false, |slot| slot.is_active()),
+        ErrorCode::InvalidRewardSlot
     );
 
-    msg!("User {} voted on proposal {}: {}", ctx.accounts.voter.key(), proposal_id, in_favor);
+    // Rewards up to now are earned at the old rate
+    platform_config.accrue_rewards_fully(&mut ctx.accounts.emission_schedule, clock.unix_timestamp)?;
+    platform_config.reward_slots[index as usize].rate_per_epoch = rate_per_epoch;
+
+    msg!("Reward slot {} rate updated to {} per epoch", index, rate_per_epoch);
+    Ok(())
+}
+
+// Deposit partner reward liquidity into a slot's vault
+#[derive(Accounts)]
+#[instruction(index: u8)]
+pub struct FundRewardSlot<'info> {
+    #[account(
+        seeds = [b"platform-config"],
+        bump = platform_config.bump,
+        constraint = platform_config.reward_slots.get(index as usize).map_or(false, |slot| slot.is_active()) @ ErrorCode::InvalidRewardSlot
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+    #[account(
+        mut,
+        seeds = [b"reward-vault", platform_config.reward_slots[index as usize].mint.as_ref()],
+        bump = platform_config.reward_slots[index as usize].vault_bump
+    )]
+    pub slot_vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub funder: Signer<'info>,
+    #[account(
+        mut,
+        constraint = funder_token_account.mint == slot_vault.mint @ ErrorCode::InvalidRewardMint
+    )]
+    pub funder_token_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn fund_reward_slot(ctx: Context<FundRewardSlot>, index: u8, amount: u64) -> Result<()> {
+    let clock = Clock::get()?;
+
+    require!(amount > 0, ErrorCode::InvalidStakeAmount);
+
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.funder_token_account.to_account_info(),
+        to: ctx.accounts.slot_vault.to_account_info(),
+        authority: ctx.accounts.funder.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+    token::transfer(cpi_ctx, amount)?;
+
+    emit!(RewardVaultFunded {
+        funder: ctx.accounts.funder.key(),
+        mint: ctx.accounts.slot_vault.mint,
+        amount,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Reward slot {} funded with {} by {}", index, amount, ctx.accounts.funder.key());
+    Ok(())
+}
+
+// Change an agent's commission (owner only)
+#[derive(Accounts)]
+pub struct SetCommission<'info> {
+    #[account(
+        mut,
+        seeds = [b"platform-config"],
+        bump = platform_config.bump
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+    #[account(
+        mut,
+        seeds = [b"emission-schedule"],
+        bump = emission_schedule.bump
+    )]
+    pub emission_schedule: Account<'info, EmissionSchedule>,
+    #[account(
+        mut,
+        seeds = [b"ai-agent", ai_agent.owner.as_ref(), &ai_agent.agent_id.to_le_bytes()],
+        bump = ai_agent.bump,
+        constraint = ai_agent.is_controlled_by(&owner.key(), owner_nft_account.as_deref()) @ ErrorCode::Unauthorized
+    )]
+    pub ai_agent: Account<'info, AiAgent>,
+    pub owner: Signer<'info>,
+    // Required once the agent is tokenized: the signer's token account holding the ownership NFT
+    pub owner_nft_account: Option<Account<'info, TokenAccount>>,
+}
+
+pub fn set_commission(ctx: Context<SetCommission>, commission_bps: u64) -> Result<()> {
+    let platform_config = &mut ctx.accounts.platform_config;
+    let ai_agent = &mut ctx.accounts.ai_agent;
+    let clock = Clock::get()?;
+
+    require!(commission_bps <= platform_config.max_commission_bps, ErrorCode::InvalidCommission);
+
+    // Rewards up to now are commissioned at the old rate, so a decrease is not retroactive either
+    platform_config.accrue_rewards_fully(&mut ctx.accounts.emission_schedule, clock.unix_timestamp)?;
+    ai_agent.settle_rewards(platform_config)?;
+
+    ai_agent.schedule_commission(commission_bps, clock.unix_timestamp, platform_config.commission_notice_period)?;
+    let effective_at = if ai_agent.commission_effective_at == 0 {
+        clock.unix_timestamp
+    } else {
+        ai_agent.commission_effective_at
+    };
+
+    emit!(CommissionChanged {
+        agent_id: ai_agent.agent_id,
+        owner: ai_agent.owner,
+        commission_bps,
+        effective_at,
+    });
+
+    msg!("Agent {} commission set to {} bps from {}", ai_agent.agent_id, commission_bps, effective_at);
+    Ok(())
+}
+
+// Assign, rotate or revoke an agent's operator key (owner only)
+#[derive(Accounts)]
+pub struct SetOperator<'info> {
+    #[account(
+        mut,
+        seeds = [b"ai-agent", ai_agent.owner.as_ref(), &ai_agent.agent_id.to_le_bytes()],
+        bump = ai_agent.bump,
+        constraint = ai_agent.is_controlled_by(&owner.key(), owner_nft_account.as_deref()) @ ErrorCode::Unauthorized
+    )]
+    pub ai_agent: Account<'info, AiAgent>,
+    pub owner: Signer<'info>,
+    // Required once the agent is tokenized: the signer's token account holding the ownership NFT
+    pub owner_nft_account: Option<Account<'info, TokenAccount>>,
+}
+
+// Passing `None` revokes the current operator
+pub fn set_operator(ctx: Context<SetOperator>, operator: Option<Pubkey>) -> Result<()> {
+    let ai_agent = &mut ctx.accounts.ai_agent;
+    let clock = Clock::get()?;
+
+    let operator = operator.unwrap_or_default();
+    require!(operator != ctx.accounts.owner.key(), ErrorCode::InvalidOperator);
+    ai_agent.operator = operator;
+    ai_agent.operator_set_by = ctx.accounts.owner.key();
+
+    emit!(AgentUpdated {
+        agent_id: ai_agent.agent_id,
+        owner: ai_agent.owner,
+        timestamp: clock.unix_timestamp,
+        new_metadata: format!("operator={}", ai_agent.operator),
+    });
+
+    msg!("AI Agent {} operator set to {}", ai_agent.agent_id, ai_agent.operator);
+    Ok(())
+}
+
+// Register a new AI agent under the next sequential ID
+#[derive(Accounts)]
+pub struct RegisterAgent<'info> {
+    #[account(
+        mut,
+        seeds = [b"platform-config"],
+        bump = platform_config.bump
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+    #[account(
+        init,
+        payer = owner,
+        space = AiAgent::SPACE,
+        seeds = [b"ai-agent", owner.key().as_ref(), &platform_config.agent_count.to_le_bytes()],
+        bump
+    )]
+    pub ai_agent: Account<'info, AiAgent>,
+    #[account(
+        init,
+        payer = owner,
+        space = AgentIndex::SPACE,
+        seeds = [b"agent-index", &platform_config.agent_count.to_le_bytes()],
+        bump
+    )]
+    pub agent_index: Account<'info, AgentIndex>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn register_ai_agent(
+    ctx: Context<RegisterAgent>,
+    name: String,
+    description: String,
+    commission_bps: u64,
+) -> Result<()> {
+    let platform_config = &mut ctx.accounts.platform_config;
+    let ai_agent = &mut ctx.accounts.ai_agent;
+    let bump = ctx.bumps.ai_agent;
+    let clock = Clock::get()?;
+
+    // Validate input lengths
+    require!(name.len() <= MAX_NAME_LENGTH, ErrorCode::MetadataTooLarge);
+    require!(description.len() <= MAX_DESCRIPTION_LENGTH, ErrorCode::MetadataTooLarge);
+    require!(commission_bps <= platform_config.max_commission_bps, ErrorCode::InvalidCommission);
+
+    // Assign the next sequential ID
+    let agent_id = platform_config.next_agent_id()?;
+
+    ai_agent.init(
+        agent_id,
+        ctx.accounts.owner.key(),
+        name,
+        description,
+        commission_bps,
+        clock.unix_timestamp,
+        bump,
+    );
+    ctx.accounts.agent_index.init(agent_id, ai_agent.key(), ai_agent.owner, ctx.bumps.agent_index);
+
+    emit!(AgentRegistered {
+        agent_id,
+        owner: ai_agent.owner,
+        timestamp: clock.unix_timestamp,
+        metadata: format!("name={};description={}", ai_agent.name, ai_agent.description),
+    });
+
+    msg!("AI Agent registered: ID {} by owner {}", agent_id, ctx.accounts.owner.key());
+    Ok(())
+}
+
+// Update an agent's name and description (owner only)
+#[derive(Accounts)]
+pub struct UpdateAiAgent<'info> {
+    #[account(
+        mut,
+        seeds = [b"ai-agent", ai_agent.owner.as_ref(), &ai_agent.agent_id.to_le_bytes()],
+        bump = ai_agent.bump,
+        constraint = ai_agent.is_controlled_by(&owner.key(), owner_nft_account.as_deref()) @ ErrorCode::Unauthorized
+    )]
+    pub ai_agent: Account<'info, AiAgent>,
+    pub owner: Signer<'info>,
+    // Required once the agent is tokenized: the signer's token account holding the ownership NFT
+    pub owner_nft_account: Option<Account<'info, TokenAccount>>,
+}
+
+pub fn update_ai_agent(ctx: Context<UpdateAiAgent>, name: String, description: String) -> Result<()> {
+    let ai_agent = &mut ctx.accounts.ai_agent;
+    let clock = Clock::get()?;
+
+    ai_agent.update_metadata(name, description)?;
+
+    emit!(AgentUpdated {
+        agent_id: ai_agent.agent_id,
+        owner: ai_agent.owner,
+        timestamp: clock.unix_timestamp,
+        new_metadata: format!("version={};name={};description={}", ai_agent.version, ai_agent.name, ai_agent.description),
+    });
+
+    msg!("AI Agent {} updated to version {}", ai_agent.agent_id, ai_agent.version);
+    Ok(())
+}
+
+// Publish an agent's typed metadata (owner only)
+#[derive(Accounts)]
+#[instruction(params: AgentMetadataParams)]
+pub struct CreateAgentMetadata<'info> {
+    #[account(
+        seeds = [b"ai-agent", ai_agent.owner.as_ref(), &ai_agent.agent_id.to_le_bytes()],
+        bump = ai_agent.bump,
+        constraint = ai_agent.is_controlled_by(&owner.key(), owner_nft_account.as_deref()) @ ErrorCode::Unauthorized
+    )]
+    pub ai_agent: Account<'info, AiAgent>,
+    #[account(
+        init,
+        payer = owner,
+        space = AgentMetadata::space(&params),
+        seeds = [b"agent-metadata", ai_agent.key().as_ref()],
+        bump
+    )]
+    pub agent_metadata: Account<'info, AgentMetadata>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    // Required once the agent is tokenized: the signer's token account holding the ownership NFT
+    pub owner_nft_account: Option<Account<'info, TokenAccount>>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_agent_metadata(ctx: Context<CreateAgentMetadata>, params: AgentMetadataParams) -> Result<()> {
+    let clock = Clock::get()?;
+
+    params.validate()?;
+    let manifest_hash = params.manifest_hash;
+    ctx.accounts.agent_metadata.init(ctx.accounts.ai_agent.key(), params, clock.unix_timestamp, ctx.bumps.agent_metadata);
+
+    emit!(AgentUpdated {
+        agent_id: ctx.accounts.ai_agent.agent_id,
+        owner: ctx.accounts.ai_agent.owner,
+        timestamp: clock.unix_timestamp,
+        new_metadata: format!("manifest_hash={:?}", manifest_hash),
+    });
+
+    msg!("Metadata published for AI Agent {}", ctx.accounts.ai_agent.agent_id);
+    Ok(())
+}
+
+// Replace an agent's typed metadata, resizing the account to fit (owner only)
+#[derive(Accounts)]
+#[instruction(params: AgentMetadataParams)]
+pub struct UpdateAgentMetadata<'info> {
+    #[account(
+        seeds = [b"ai-agent", ai_agent.owner.as_ref(), &ai_agent.agent_id.to_le_bytes()],
+        bump = ai_agent.bump,
+        constraint = ai_agent.is_controlled_by(&owner.key(), owner_nft_account.as_deref()) @ ErrorCode::Unauthorized
+    )]
+    pub ai_agent: Account<'info, AiAgent>,
+    #[account(
+        mut,
+        seeds = [b"agent-metadata", ai_agent.key().as_ref()],
+        bump = agent_metadata.bump,
+        realloc = AgentMetadata::space(&params),
+        realloc::payer = owner,
+        realloc::zero = false
+    )]
+    pub agent_metadata: Account<'info, AgentMetadata>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    // Required once the agent is tokenized: the signer's token account holding the ownership NFT
+    pub owner_nft_account: Option<Account<'info, TokenAccount>>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn update_agent_metadata(ctx: Context<UpdateAgentMetadata>, params: AgentMetadataParams) -> Result<()> {
+    let agent_metadata = &mut ctx.accounts.agent_metadata;
+    let clock = Clock::get()?;
+
+    params.validate()?;
+    agent_metadata.params = params;
+    agent_metadata.updated_at = clock.unix_timestamp;
+
+    emit!(AgentUpdated {
+        agent_id: ctx.accounts.ai_agent.agent_id,
+        owner: ctx.accounts.ai_agent.owner,
+        timestamp: clock.unix_timestamp,
+        new_metadata: format!("manifest_hash={:?}", agent_metadata.params.manifest_hash),
+    });
+
+    msg!("Metadata updated for AI Agent {}", ctx.accounts.ai_agent.agent_id);
+    Ok(())
+}
+
+// Move an agent through its lifecycle (owner only)
+#[derive(Accounts)]
+pub struct SetAgentStatus<'info> {
+    #[account(
+        seeds = [b"platform-config"],
+        bump = platform_config.bump
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+    #[account(
+        mut,
+        seeds = [b"ai-agent", ai_agent.owner.as_ref(), &ai_agent.agent_id.to_le_bytes()],
+        bump = ai_agent.bump,
+        constraint = ai_agent.is_controlled_by(&owner.key(), owner_nft_account.as_deref()) @ ErrorCode::Unauthorized
+    )]
+    pub ai_agent: Account<'info, AiAgent>,
+    pub owner: Signer<'info>,
+    // Required once the agent is tokenized: the signer's token account holding the ownership NFT
+    pub owner_nft_account: Option<Account<'info, TokenAccount>>,
+}
+
+pub fn set_agent_status(ctx: Context<SetAgentStatus>, status: AgentStatus) -> Result<()> {
+    let ai_agent = &mut ctx.accounts.ai_agent;
+    let clock = Clock::get()?;
+
+    ai_agent.transition_to(status, clock.unix_timestamp, ctx.accounts.platform_config.unbonding_period)?;
+
+    emit!(AgentUpdated {
+        agent_id: ai_agent.agent_id,
+        owner: ai_agent.owner,
+        timestamp: clock.unix_timestamp,
+        new_metadata: format!("status={:?}", status),
+    });
+
+    msg!("AI Agent {} is now {:?}", ai_agent.agent_id, status);
+    Ok(())
+}
+
+// Close a retired agent and return its rent to the owner
+#[derive(Accounts)]
+pub struct CloseAgent<'info> {
+    #[account(
+        mut,
+        seeds = [b"ai-agent", ai_agent.owner.as_ref(), &ai_agent.agent_id.to_le_bytes()],
+        bump = ai_agent.bump,
+        constraint = ai_agent.is_controlled_by(&owner.key(), owner_nft_account.as_deref()) @ ErrorCode::Unauthorized,
+        constraint = ai_agent.status == AgentStatus::Retired @ ErrorCode::AgentNotRetired,
+        constraint = ai_agent.staked_amount == 0 && !ai_agent.has_unclaimed_rewards() @ ErrorCode::AgentNotRetired,
+        constraint = !ai_agent.slash_pending @ ErrorCode::AgentSlashPending,
+        constraint = ai_agent.outgoing_exposures == 0 && ai_agent.unbonding_tickets == 0 @ ErrorCode::AgentNotRetired,
+        close = owner
+    )]
+    pub ai_agent: Account<'info, AiAgent>,
+    #[account(
+        mut,
+        seeds = [b"agent-index", &ai_agent.agent_id.to_le_bytes()],
+        bump = agent_index.bump,
+        close = owner
+    )]
+    pub agent_index: Account<'info, AgentIndex>,
+    /// CHECK: address is the agent's metadata PDA; closed below if the metadata was ever published
+    #[account(
+        mut,
+        seeds = [b"agent-metadata", ai_agent.key().as_ref()],
+        bump
+    )]
+    pub agent_metadata: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    // Required once the agent is tokenized: the signer's token account holding the ownership NFT
+    pub owner_nft_account: Option<Account<'info, TokenAccount>>,
+}
+
+pub fn close_agent(ctx: Context<CloseAgent>) -> Result<()> {
+    // Metadata is optional, so its PDA only holds an account if the owner published some
+    let agent_metadata = ctx.accounts.agent_metadata.to_account_info();
+    if agent_metadata.owner == ctx.program_id && agent_metadata.lamports() > 0 {
+        anchor_lang::common::close(agent_metadata, ctx.accounts.owner.to_account_info())?;
+    }
+
+    msg!("AI Agent {} closed by owner {}", ctx.accounts.ai_agent.agent_id, ctx.accounts.owner.key());
+    Ok(())
+}
+
+// Record a new evolution chapter for an agent (owner or operator)
+#[derive(Accounts)]
+pub struct EvolveAiAgent<'info> {
+    #[account(
+        seeds = [b"platform-config"],
+        bump = platform_config.bump
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+    #[account(
+        mut,
+        seeds = [b"ai-agent", ai_agent.owner.as_ref(), &ai_agent.agent_id.to_le_bytes()],
+        bump = ai_agent.bump,
+        constraint = ai_agent.is_operated_by(&authority.key(), owner_nft_account.as_deref()) @ ErrorCode::Unauthorized
+    )]
+    pub ai_agent: Account<'info, AiAgent>,
+    pub authority: Signer<'info>,
+    // Required once the agent is tokenized: the token account holding the ownership NFT, owned by the
+    // signer or, when the operator signs, by the holder that appointed it
+    pub owner_nft_account: Option<Account<'info, TokenAccount>>,
+}
+
+pub fn evolve_ai_agent(
+    ctx: Context<EvolveAiAgent>,
+    behavior_hash: [u8; 32],
+    model_hash: [u8; 32],
+    model_version: u16,
+    chapter_id: u32,
+    interaction_count: u64,
+) -> Result<()> {
+    let ai_agent = &mut ctx.accounts.ai_agent;
+    let clock = Clock::get()?;
+
+    ai_agent.evolve(
+        behavior_hash,
+        model_hash,
+        model_version,
+        chapter_id,
+        interaction_count,
+        clock.unix_timestamp,
+        ctx.accounts.platform_config.min_evolution_interval,
+    )?;
+
+    emit!(AiAgentEvolved {
+        agent: ai_agent.key(),
+        agent_id: ai_agent.agent_id,
+        chapter_id,
+        behavior_hash,
+        model_hash,
+        model_version,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("AI Agent {} evolved to chapter {} on model version {}", ai_agent.agent_id, chapter_id, model_version);
+    Ok(())
+}
+
+// Report that an agent is alive (owner or operator)
+#[derive(Accounts)]
+pub struct Heartbeat<'info> {
+    #[account(
+        mut,
+        seeds = [b"ai-agent", ai_agent.owner.as_ref(), &ai_agent.agent_id.to_le_bytes()],
+        bump = ai_agent.bump,
+        constraint = ai_agent.is_operated_by(&authority.key(), owner_nft_account.as_deref()) @ ErrorCode::Unauthorized
+    )]
+    pub ai_agent: Account<'info, AiAgent>,
+    pub authority: Signer<'info>,
+    // Required once the agent is tokenized: the token account holding the ownership NFT, owned by the
+    // signer or, when the operator signs, by the holder that appointed it
+    pub owner_nft_account: Option<Account<'info, TokenAccount>>,
+}
+
+pub fn heartbeat(ctx: Context<Heartbeat>) -> Result<()> {
+    let ai_agent = &mut ctx.accounts.ai_agent;
+    let clock = Clock::get()?;
+
+    ai_agent.record_heartbeat(clock.slot, clock.unix_timestamp);
+
+    msg!("Heartbeat from AI Agent {} at slot {}", ai_agent.agent_id, clock.slot);
+    Ok(())
+}
+
+// Jail an agent that missed the liveness window (permissionless)
+#[derive(Accounts)]
+pub struct JailAgent<'info> {
+    #[account(
+        mut,
+        seeds = [b"platform-config"],
+        bump = platform_config.bump
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+    #[account(
+        mut,
+        seeds = [b"emission-schedule"],
+        bump = emission_schedule.bump
+    )]
+    pub emission_schedule: Account<'info, EmissionSchedule>,
+    #[account(
+        mut,
+        seeds = [b"ai-agent", ai_agent.owner.as_ref(), &ai_agent.agent_id.to_le_bytes()],
+        bump = ai_agent.bump
+    )]
+    pub ai_agent: Account<'info, AiAgent>,
+}
+
+pub fn jail_agent(ctx: Context<JailAgent>) -> Result<()> {
+    let platform_config = &mut ctx.accounts.platform_config;
+    let ai_agent = &mut ctx.accounts.ai_agent;
+    let clock = Clock::get()?;
+
+    require!(
+        ai_agent.can_be_jailed(clock.unix_timestamp, platform_config.liveness_window)?,
+        ErrorCode::AgentStillLive
+    );
+
+    // Settle what the agent earned up to now before it stops earning
+    platform_config.accrue_rewards_fully(&mut ctx.accounts.emission_schedule, clock.unix_timestamp)?;
+    ai_agent.settle_rewards(platform_config)?;
+    platform_config.set_agent_jailed(ai_agent, true, clock.unix_timestamp)?;
+
+    emit!(AgentJailed {
+        agent: ai_agent.key(),
+        agent_id: ai_agent.agent_id,
+        last_heartbeat_timestamp: ai_agent.last_heartbeat_timestamp,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("AI Agent {} jailed for inactivity", ai_agent.agent_id);
+    Ok(())
+}
+
+// Release a jailed agent once it is live again (owner only)
+#[derive(Accounts)]
+pub struct UnjailAgent<'info> {
+    #[account(
+        mut,
+        seeds = [b"platform-config"],
+        bump = platform_config.bump
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+    #[account(
+        mut,
+        seeds = [b"emission-schedule"],
+        bump = emission_schedule.bump
+    )]
+    pub emission_schedule: Account<'info, EmissionSchedule>,
+    #[account(
+        mut,
+        seeds = [b"ai-agent", ai_agent.owner.as_ref(), &ai_agent.agent_id.to_le_bytes()],
+        bump = ai_agent.bump,
+        constraint = ai_agent.is_controlled_by(&owner.key(), owner_nft_account.as_deref()) @ ErrorCode::Unauthorized
+    )]
+    pub ai_agent: Account<'info, AiAgent>,
+    pub owner: Signer<'info>,
+    // Required once the agent is tokenized: the signer's token account holding the ownership NFT
+    pub owner_nft_account: Option<Account<'info, TokenAccount>>,
+}
+
+pub fn unjail(ctx: Context<UnjailAgent>) -> Result<()> {
+    let platform_config = &mut ctx.accounts.platform_config;
+    let ai_agent = &mut ctx.accounts.ai_agent;
+    let clock = Clock::get()?;
+
+    require!(ai_agent.jailed, ErrorCode::AgentNotJailed);
+    require!(
+        ai_agent.can_be_unjailed(clock.unix_timestamp, platform_config.min_jail_duration)?,
+        ErrorCode::UnjailTooSoon
+    );
+
+    // Accrue up to now first so the agent does not share in what was emitted while it was jailed
+    platform_config.accrue_rewards_fully(&mut ctx.accounts.emission_schedule, clock.unix_timestamp)?;
+    ai_agent.settle_rewards(platform_config)?;
+    platform_config.set_agent_jailed(ai_agent, false, clock.unix_timestamp)?;
+
+    emit!(AgentUnjailed {
+        agent: ai_agent.key(),
+        agent_id: ai_agent.agent_id,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("AI Agent {} unjailed", ai_agent.agent_id);
+    Ok(())
+}
+
+// Update an agent's performance score (evaluator only)
+#[derive(Accounts)]
+pub struct UpdatePerformanceScore<'info> {
+    #[account(
+        mut,
+        seeds = [b"platform-config"],
+        bump = platform_config.bump,
+        has_one = evaluator @ ErrorCode::Unauthorized
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+    #[account(
+        mut,
+        seeds = [b"emission-schedule"],
+        bump = emission_schedule.bump
+    )]
+    pub emission_schedule: Account<'info, EmissionSchedule>,
+    #[account(
+        mut,
+        seeds = [b"ai-agent", ai_agent.owner.as_ref(), &ai_agent.agent_id.to_le_bytes()],
+        bump = ai_agent.bump
+    )]
+    pub ai_agent: Account<'info, AiAgent>,
+    pub evaluator: Signer<'info>,
+}
+
+pub fn update_performance_score(ctx: Context<UpdatePerformanceScore>, performance_score: u64) -> Result<()> {
+    let platform_config = &mut ctx.accounts.platform_config;
+    let emission_schedule = &mut ctx.accounts.emission_schedule;
+    let ai_agent = &mut ctx.accounts.ai_agent;
+    let clock = Clock::get()?;
+
+    // Settle rewards earned under the old score before it changes
+    platform_config.accrue_rewards_fully(emission_schedule, clock.unix_timestamp)?;
+    ai_agent.settle_rewards(platform_config)?;
+
+    let old_score = ai_agent.effective_score();
+    ai_agent.performance_score = performance_score;
+    platform_config.apply_score_change(old_score, ai_agent.effective_score())?;
+    ai_agent.reset_reward_debts(platform_config)?;
+
+    emit!(AgentUpdated {
+        agent_id: ai_agent.agent_id,
+        owner: ai_agent.owner,
+        timestamp: clock.unix_timestamp,
+        new_metadata: format!("performance_score={}", performance_score),
+    });
+
+    msg!("Agent {} performance score set to {}", ai_agent.agent_id, performance_score);
+    Ok(())
+}
+
+// Stake (delegate) tokens on any registered AI agent
+#[derive(Accounts)]
+#[instruction(agent_owner: Pubkey, agent_id: u64)]
+pub struct StakeOnAgent<'info> {
+    #[account(
+        mut,
+        seeds = [b"platform-config"],
+        bump = platform_config.bump
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+    #[account(
+        mut,
+        seeds = [b"emission-schedule"],
+        bump = emission_schedule.bump
+    )]
+    pub emission_schedule: Account<'info, EmissionSchedule>,
+    #[account(
+        mut,
+        seeds = [b"ai-agent", agent_owner.as_ref(), &agent_id.to_le_bytes()],
+        bump = ai_agent.bump
+    )]
+    pub ai_agent: Account<'info, AiAgent>,
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = UserStake::SPACE,
+        seeds = [b"user-stake", user.key().as_ref()],
+        bump
+    )]
+    pub user_stake: Account<'info, UserStake>,
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = StakePosition::SPACE,
+        seeds = [b"stake-position", user.key().as_ref(), ai_agent.key().as_ref()],
+        bump
+    )]
+    pub stake_position: Account<'info, StakePosition>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(mut)]
+    pub user_token_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"stake-vault"],
+        bump = platform_config.stake_vault_bump,
+        token::mint = platform_config.stake_mint,
+        token::authority = platform_config
+    )]
+    pub stake_vault: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn stake_on_agent(
+    ctx: Context<StakeOnAgent>,
+    agent_owner: Pubkey,
+    agent_id: u64,
+    amount: u64,
+    lock_duration: i64,
+) -> Result<()> {
+    let platform_config = &mut ctx.accounts.platform_config;
+    let emission_schedule = &mut ctx.accounts.emission_schedule;
+    let ai_agent = &mut ctx.accounts.ai_agent;
+    let user_stake = &mut ctx.accounts.user_stake;
+    let stake_position = &mut ctx.accounts.stake_position;
+    let clock = Clock::get()?;
+
+    // Validate stake amount
+    require!(amount >= platform_config.min_stake_amount, ErrorCode::InvalidStakeAmount);
+    require!(ai_agent.status == AgentStatus::Active, ErrorCode::AgentNotActive);
+    require!(!ai_agent.jailed, ErrorCode::AgentJailed);
+    require!(!ai_agent.slash_pending, ErrorCode::AgentSlashPending);
+
+    // Initialize user stake if newly created
+    if user_stake.user == Pubkey::default() {
+        user_stake.init(ctx.accounts.user.key(), ctx.bumps.user_stake);
+    }
+
+    // Open a position on the agent if this is the first stake on it
+    if stake_position.user == Pubkey::default() {
+        stake_position.init(
+            ctx.accounts.user.key(),
+            ai_agent.key(),
+            agent_id,
+            clock.unix_timestamp,
+            ctx.bumps.stake_position,
+        );
+    }
+    if stake_position.amount == 0 {
+        user_stake.position_count = user_stake.position_count.checked_add(1).ok_or(ErrorCode::InvalidStakeAmount)?;
+        ai_agent.position_count = ai_agent.position_count.checked_add(1).ok_or(ErrorCode::InvalidStakeAmount)?;
+        // An empty position holds nothing that earlier slashes could apply to
+        stake_position.slash_count = ai_agent.slash_count;
+    }
+
+    // Settle rewards earned on the previous amount before it changes
+    platform_config.accrue_rewards_fully(emission_schedule, clock.unix_timestamp)?;
+    ai_agent.settle_rewards(platform_config)?;
+    ai_agent.settle_position(stake_position)?;
+
+    // Apply the requested lock; the whole position shares one lock
+    stake_position.apply_lock(clock.unix_timestamp, lock_duration)?;
+
+    // Activate what has warmed up before the deposit restarts the warmup
+    platform_config.activate_stake(stake_position, clock.unix_timestamp)?;
+
+    // Update stake amounts; the deposit only counts once it has activated
+    let activation_epoch = platform_config
+        .current_epoch(clock.unix_timestamp)
+        .checked_add(platform_config.warmup_epochs)
+        .ok_or(ErrorCode::InvalidStakeAmount)?;
+    stake_position.add_activating(amount, activation_epoch)?;
+    platform_config.sync_activating(0, amount)?;
+    user_stake.staked_amount = user_stake.staked_amount.checked_add(amount).ok_or(ErrorCode::InvalidStakeAmount)?;
+    ai_agent.staked_amount = ai_agent.staked_amount.checked_add(amount).ok_or(ErrorCode::InvalidStakeAmount)?;
+    platform_config.total_staked = platform_config.total_staked.checked_add(amount).ok_or(ErrorCode::InvalidStakeAmount)?;
+    platform_config.activate_stake(stake_position, clock.unix_timestamp)?;
+    let (old_weight, new_weight) = stake_position.refresh_weight()?;
+    platform_config.apply_position_weight_change(ai_agent, old_weight, new_weight)?;
+    stake_position.reset_reward_debt(ai_agent.acc_reward_per_share)?;
+    stake_position.reset_slot_reward_debts(&ai_agent.slot_acc_per_share)?;
+
+    // Update timestamps
+    user_stake.last_stake_update = clock.unix_timestamp;
+
+    // Transfer tokens from user to the stake vault
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.user_token_account.to_account_info(),
+        to: ctx.accounts.stake_vault.to_account_info(),
+        authority: ctx.accounts.user.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+    token::transfer(cpi_ctx, amount)?;
+
+    emit!(StakeDeposited {
+        user: ctx.accounts.user.key(),
+        agent_id,
+        amount,
+        timestamp: clock.unix_timestamp,
+        staking_duration: lock_duration as u64,
+    });
+
+    msg!("User {} staked {} on agent {} of {} locked for {}s", ctx.accounts.user.key(), amount, agent_id, agent_owner, lock_duration);
+    Ok(())
+}
+
+// Unstake tokens from an AI agent into an unbonding ticket
+#[derive(Accounts)]
+#[instruction(agent_owner: Pubkey, agent_id: u64)]
+pub struct UnstakeFromAgent<'info> {
+    #[account(
+        mut,
+        seeds = [b"platform-config"],
+        bump = platform_config.bump
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+    #[account(
+        mut,
+        seeds = [b"emission-schedule"],
+        bump = emission_schedule.bump
+    )]
+    pub emission_schedule: Account<'info, EmissionSchedule>,
+    #[account(
+        mut,
+        seeds = [b"ai-agent", agent_owner.as_ref(), &agent_id.to_le_bytes()],
+        bump = ai_agent.bump
+    )]
+    pub ai_agent: Account<'info, AiAgent>,
+    #[account(
+        mut,
+        seeds = [b"user-stake", user.key().as_ref()],
+        bump = user_stake.bump,
+        has_one = user @ ErrorCode::Unauthorized
+    )]
+    pub user_stake: Account<'info, UserStake>,
+    #[account(
+        mut,
+        seeds = [b"stake-position", user.key().as_ref(), ai_agent.key().as_ref()],
+        bump = stake_position.bump,
+        has_one = user @ ErrorCode::Unauthorized
+    )]
+    pub stake_position: Account<'info, StakePosition>,
+    #[account(
+        init,
+        payer = user,
+        space = UnbondingTicket::SPACE,
+        seeds = [b"unbonding-ticket", user.key().as_ref(), &user_stake.next_ticket_id.to_le_bytes()],
+        bump
+    )]
+    pub unbonding_ticket: Account<'info, UnbondingTicket>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn unstake_from_agent(
+    ctx: Context<UnstakeFromAgent>,
+    _agent_owner: Pubkey,
+    agent_id: u64,
+    amount: u64,
+) -> Result<()> {
+    let platform_config = &mut ctx.accounts.platform_config;
+    let emission_schedule = &mut ctx.accounts.emission_schedule;
+    let ai_agent = &mut ctx.accounts.ai_agent;
+    let user_stake = &mut ctx.accounts.user_stake;
+    let stake_position = &mut ctx.accounts.stake_position;
+    let clock = Clock::get()?;
+
+    // Validate unstake amount against the position balance
+    require!(amount > 0, ErrorCode::InvalidStakeAmount);
+    require!(amount <= stake_position.amount, ErrorCode::InvalidUnstakeAmount);
+    require!(amount <= stake_position.unexposed_amount(), ErrorCode::StakeExposed);
+    require!(!stake_position.is_locked(clock.unix_timestamp), ErrorCode::StakeLocked);
+
+    let (ticket_id, release_time) = begin_unbonding(
+        platform_config,
+        emission_schedule,
+        ai_agent,
+        user_stake,
+        stake_position,
+        &mut ctx.accounts.unbonding_ticket,
+        ctx.bumps.unbonding_ticket,
+        amount,
+        clock.unix_timestamp,
+    )?;
+
+    emit!(UnbondingStarted {
+        user: ctx.accounts.user.key(),
+        agent_id,
+        ticket_id,
+        amount,
+        release_time,
+    });
+
+    msg!("User {} unstaked {} from agent {}, ticket {}", ctx.accounts.user.key(), amount, agent_id, ticket_id);
+    Ok(())
+}
+
+// Move `amount` of a position into a new unbonding ticket: settles rewards on the old amount,
+// reverses the stake accounting and queues the tokens. Returns (ticket_id, release_time).
+#[allow(clippy::too_many_arguments)]
+fn begin_unbonding(
+    platform_config: &mut PlatformConfig,
+    emission_schedule: &mut EmissionSchedule,
+    ai_agent: &mut AiAgent,
+    user_stake: &mut UserStake,
+    stake_position: &mut StakePosition,
+    unbonding_ticket: &mut UnbondingTicket,
+    ticket_bump: u8,
+    amount: u64,
+    now: i64,
+) -> Result<(u64, i64)> {
+    // Stake under a pending slash stays put until the slash is resolved
+    require!(!ai_agent.slash_pending, ErrorCode::AgentSlashPending);
+
+    // Settle rewards earned on the previous amount before it changes
+    platform_config.accrue_rewards_fully(emission_schedule, now)?;
+    ai_agent.settle_rewards(platform_config)?;
+    ai_agent.settle_position(stake_position)?;
+    stake_position.expire_lock(now);
+    platform_config.activate_stake(stake_position, now)?;
+
+    // Reverse the stake accounting; stake still activating leaves first
+    let activating = stake_position.activating_amount;
+    stake_position.remove_stake(amount)?;
+    platform_config.sync_activating(activating, stake_position.activating_amount)?;
+    user_stake.staked_amount = user_stake.staked_amount.checked_sub(amount).ok_or(ErrorCode::InvalidUnstakeAmount)?;
+    ai_agent.staked_amount = ai_agent.staked_amount.checked_sub(amount).ok_or(ErrorCode::InvalidUnstakeAmount)?;
+    platform_config.total_staked = platform_config.total_staked.checked_sub(amount).ok_or(ErrorCode::InvalidUnstakeAmount)?;
+    let (old_weight, new_weight) = stake_position.refresh_weight()?;
+    platform_config.apply_position_weight_change(ai_agent, old_weight, new_weight)?;
+    stake_position.reset_reward_debt(ai_agent.acc_reward_per_share)?;
+    stake_position.reset_slot_reward_debts(&ai_agent.slot_acc_per_share)?;
+
+    // The position stays open for rewards bookkeeping but no longer counts as open
+    if stake_position.amount == 0 {
+        user_stake.position_count = user_stake.position_count.saturating_sub(1);
+        ai_agent.position_count = ai_agent.position_count.saturating_sub(1);
+    }
+    user_stake.last_stake_update = now;
+
+    // Queue the tokens for deactivation and withdrawal after the unbonding period and cooldown
+    let ticket_id = user_stake.next_ticket_id;
+    let release_time = platform_config.schedule_unbonding(amount, now)?;
+    unbonding_ticket.init(
+        stake_position.user,
+        ai_agent.key(),
+        ai_agent.agent_id,
+        ticket_id,
+        amount,
+        now,
+        release_time,
+        ai_agent.slash_count,
+        ticket_bump,
+    );
+    user_stake.next_ticket_id = ticket_id.checked_add(1).ok_or(ErrorCode::InvalidStakeAmount)?;
+    ai_agent.unbonding_tickets = ai_agent.unbonding_tickets.checked_add(1).ok_or(ErrorCode::InvalidStakeAmount)?;
+    Ok((ticket_id, release_time))
+}
+
+// Unbond a delegator's whole position from a retiring agent, ignoring any lock (permissionless)
+#[derive(Accounts)]
+pub struct ForceUnbond<'info> {
+    #[account(
+        mut,
+        seeds = [b"platform-config"],
+        bump = platform_config.bump
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+    #[account(
+        mut,
+        seeds = [b"emission-schedule"],
+        bump = emission_schedule.bump
+    )]
+    pub emission_schedule: Account<'info, EmissionSchedule>,
+    #[account(
+        mut,
+        seeds = [b"ai-agent", ai_agent.owner.as_ref(), &ai_agent.agent_id.to_le_bytes()],
+        bump = ai_agent.bump,
+        constraint = ai_agent.status == AgentStatus::Retiring @ ErrorCode::AgentNotRetiring
+    )]
+    pub ai_agent: Account<'info, AiAgent>,
+    #[account(
+        mut,
+        seeds = [b"user-stake", stake_position.user.as_ref()],
+        bump = user_stake.bump
+    )]
+    pub user_stake: Account<'info, UserStake>,
+    #[account(
+        mut,
+        seeds = [b"stake-position", stake_position.user.as_ref(), ai_agent.key().as_ref()],
+        bump = stake_position.bump
+    )]
+    pub stake_position: Account<'info, StakePosition>,
+    #[account(
+        init,
+        payer = caller,
+        space = UnbondingTicket::SPACE,
+        seeds = [b"unbonding-ticket", stake_position.user.as_ref(), &user_stake.next_ticket_id.to_le_bytes()],
+        bump
+    )]
+    pub unbonding_ticket: Account<'info, UnbondingTicket>,
+    #[account(mut)]
+    pub caller: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn force_unbond(ctx: Context<ForceUnbond>) -> Result<()> {
+    let clock = Clock::get()?;
+    // Stake still exposed to another agent's slashes stays until the exposure is released
+    let amount = ctx.accounts.stake_position.unexposed_amount();
+
+    require!(amount > 0, ErrorCode::InvalidUnstakeAmount);
+
+    let (ticket_id, release_time) = begin_unbonding(
+        &mut ctx.accounts.platform_config,
+        &mut ctx.accounts.emission_schedule,
+        &mut ctx.accounts.ai_agent,
+        &mut ctx.accounts.user_stake,
+        &mut ctx.accounts.stake_position,
+        &mut ctx.accounts.unbonding_ticket,
+        ctx.bumps.unbonding_ticket,
+        amount,
+        clock.unix_timestamp,
+    )?;
+
+    emit!(UnbondingStarted {
+        user: ctx.accounts.stake_position.user,
+        agent_id: ctx.accounts.ai_agent.agent_id,
+        ticket_id,
+        amount,
+        release_time,
+    });
+
+    msg!(
+        "Position of {} on retiring agent {} force-unbonded: {}, ticket {}",
+        ctx.accounts.stake_position.user,
+        ctx.accounts.ai_agent.agent_id,
+        amount,
+        ticket_id
+    );
+    Ok(())
+}
+
+// Drop the bonus of a position whose lock has run out, so it stops earning at the boosted
+// weight before its owner next touches it (permissionless)
+#[derive(Accounts)]
+pub struct RefreshPosition<'info> {
+    #[account(
+        mut,
+        seeds = [b"platform-config"],
+        bump = platform_config.bump
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+    #[account(
+        mut,
+        seeds = [b"emission-schedule"],
+        bump = emission_schedule.bump
+    )]
+    pub emission_schedule: Account<'info, EmissionSchedule>,
+    #[account(
+        mut,
+        seeds = [b"ai-agent", ai_agent.owner.as_ref(), &ai_agent.agent_id.to_le_bytes()],
+        bump = ai_agent.bump
+    )]
+    pub ai_agent: Account<'info, AiAgent>,
+    #[account(
+        mut,
+        seeds = [b"stake-position", stake_position.user.as_ref(), ai_agent.key().as_ref()],
+        bump = stake_position.bump
+    )]
+    pub stake_position: Account<'info, StakePosition>,
+}
+
+pub fn refresh_position(ctx: Context<RefreshPosition>) -> Result<()> {
+    let platform_config = &mut ctx.accounts.platform_config;
+    let ai_agent = &mut ctx.accounts.ai_agent;
+    let stake_position = &mut ctx.accounts.stake_position;
+    let clock = Clock::get()?;
+
+    require!(
+        stake_position.multiplier_bps > BASE_MULTIPLIER_BPS && !stake_position.is_locked(clock.unix_timestamp),
+        ErrorCode::NoExpiredLock
+    );
+
+    // Everything up to now was earned under the lock
+    platform_config.accrue_rewards_fully(&mut ctx.accounts.emission_schedule, clock.unix_timestamp)?;
+    ai_agent.settle_rewards(platform_config)?;
+    ai_agent.settle_position(stake_position)?;
+
+    stake_position.expire_lock(clock.unix_timestamp);
+    let (old_weight, new_weight) = stake_position.refresh_weight()?;
+    platform_config.apply_position_weight_change(ai_agent, old_weight, new_weight)?;
+    stake_position.reset_reward_debt(ai_agent.acc_reward_per_share)?;
+    stake_position.reset_slot_reward_debts(&ai_agent.slot_acc_per_share)?;
+
+    msg!(
+        "Expired lock dropped on position of {} on agent {}: weight {} -> {}",
+        stake_position.user,
+        ai_agent.agent_id,
+        old_weight,
+        new_weight
+    );
+    Ok(())
+}
+
+// Activate a position's warmed-up stake within this epoch's activation budget (permissionless)
+#[derive(Accounts)]
+pub struct ActivateStake<'info> {
+    #[account(
+        mut,
+        seeds = [b"platform-config"],
+        bump = platform_config.bump
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+    #[account(
+        mut,
+        seeds = [b"emission-schedule"],
+        bump = emission_schedule.bump
+    )]
+    pub emission_schedule: Account<'info, EmissionSchedule>,
+    #[account(
+        mut,
+        seeds = [b"ai-agent", ai_agent.owner.as_ref(), &ai_agent.agent_id.to_le_bytes()],
+        bump = ai_agent.bump
+    )]
+    pub ai_agent: Account<'info, AiAgent>,
+    #[account(
+        mut,
+        seeds = [b"stake-position", stake_position.user.as_ref(), ai_agent.key().as_ref()],
+        bump = stake_position.bump,
+        constraint = stake_position.activating_amount > 0 @ ErrorCode::InvalidStakeAmount
+    )]
+    pub stake_position: Account<'info, StakePosition>,
+}
+
+pub fn activate_stake(ctx: Context<ActivateStake>) -> Result<()> {
+    let platform_config = &mut ctx.accounts.platform_config;
+    let ai_agent = &mut ctx.accounts.ai_agent;
+    let stake_position = &mut ctx.accounts.stake_position;
+    let clock = Clock::get()?;
+
+    // Settle rewards earned on the active stake before it grows
+    platform_config.accrue_rewards_fully(&mut ctx.accounts.emission_schedule, clock.unix_timestamp)?;
+    ai_agent.settle_rewards(platform_config)?;
+    ai_agent.settle_position(stake_position)?;
+
+    stake_position.expire_lock(clock.unix_timestamp);
+    platform_config.activate_stake(stake_position, clock.unix_timestamp)?;
+    let (old_weight, new_weight) = stake_position.refresh_weight()?;
+    platform_config.apply_position_weight_change(ai_agent, old_weight, new_weight)?;
+    stake_position.reset_reward_debt(ai_agent.acc_reward_per_share)?;
+    stake_position.reset_slot_reward_debts(&ai_agent.slot_acc_per_share)?;
+
+    msg!(
+        "Position of {} on agent {}: {} active, {} still activating",
+        stake_position.user,
+        ai_agent.agent_id,
+        stake_position.active_amount(),
+        stake_position.activating_amount
+    );
+    Ok(())
+}
+
+// Move stake from one agent to another without unbonding. The moved stake stays exposed to
+// slashes on the source agent for one unbonding period.
+#[derive(Accounts)]
+pub struct Redelegate<'info> {
+    #[account(
+        mut,
+        seeds = [b"platform-config"],
+        bump = platform_config.bump
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+    #[account(
+        mut,
+        seeds = [b"emission-schedule"],
+        bump = emission_schedule.bump
+    )]
+    pub emission_schedule: Account<'info, EmissionSchedule>,
+    #[account(
+        mut,
+        seeds = [b"ai-agent", source_agent.owner.as_ref(), &source_agent.agent_id.to_le_bytes()],
+        bump = source_agent.bump,
+        constraint = !source_agent.slash_pending @ ErrorCode::AgentSlashPending
+    )]
+    pub source_agent: Account<'info, AiAgent>,
+    #[account(
+        mut,
+        seeds = [b"ai-agent", target_agent.owner.as_ref(), &target_agent.agent_id.to_le_bytes()],
+        bump = target_agent.bump,
+        constraint = target_agent.key() != source_agent.key() @ ErrorCode::InvalidRedelegation,
+        constraint = target_agent.status == AgentStatus::Active @ ErrorCode::AgentNotActive,
+        constraint = !target_agent.jailed @ ErrorCode::AgentJailed,
+        constraint = !target_agent.slash_pending @ ErrorCode::AgentSlashPending
+    )]
+    pub target_agent: Account<'info, AiAgent>,
+    #[account(
+        mut,
+        seeds = [b"user-stake", user.key().as_ref()],
+        bump = user_stake.bump,
+        has_one = user @ ErrorCode::Unauthorized
+    )]
+    pub user_stake: Account<'info, UserStake>,
+    #[account(
+        mut,
+        seeds = [b"stake-position", user.key().as_ref(), source_agent.key().as_ref()],
+        bump = source_position.bump,
+        has_one = user @ ErrorCode::Unauthorized
+    )]
+    pub source_position: Account<'info, StakePosition>,
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = StakePosition::SPACE,
+        seeds = [b"stake-position", user.key().as_ref(), target_agent.key().as_ref()],
+        bump
+    )]
+    pub target_position: Account<'info, StakePosition>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn redelegate(ctx: Context<Redelegate>, amount: u64) -> Result<()> {
+    let platform_config = &mut ctx.accounts.platform_config;
+    let emission_schedule = &mut ctx.accounts.emission_schedule;
+    let source_agent = &mut ctx.accounts.source_agent;
+    let target_agent = &mut ctx.accounts.target_agent;
+    let user_stake = &mut ctx.accounts.user_stake;
+    let source_position = &mut ctx.accounts.source_position;
+    let target_position = &mut ctx.accounts.target_position;
+    let clock = Clock::get()?;
+    let now = clock.unix_timestamp;
+
+    require!(amount > 0 && amount <= source_position.unexposed_amount(), ErrorCode::InvalidUnstakeAmount);
+    require!(!source_position.is_locked(now), ErrorCode::StakeLocked);
+    require!(
+        source_position.can_redelegate(now, platform_config.redelegation_cooldown)?,
+        ErrorCode::RedelegationCooldown
+    );
+
+    if target_position.user == Pubkey::default() {
+        target_position.init(ctx.accounts.user.key(), target_agent.key(), target_agent.agent_id, now, ctx.bumps.target_position);
+    }
+    // A position carries exposure to one source agent at a time
+    require!(!target_position.has_exposure(), ErrorCode::StakeExposed);
+    require!(
+        target_position.can_redelegate(now, platform_config.redelegation_cooldown)?,
+        ErrorCode::RedelegationCooldown
+    );
+    if target_position.amount == 0 {
+        user_stake.position_count = user_stake.position_count.checked_add(1).ok_or(ErrorCode::InvalidStakeAmount)?;
+        target_agent.position_count = target_agent.position_count.checked_add(1).ok_or(ErrorCode::InvalidStakeAmount)?;
+        target_position.slash_count = target_agent.slash_count;
+    }
+
+    // Settle both positions on their current amounts before anything moves
+    platform_config.accrue_rewards_fully(emission_schedule, now)?;
+    source_agent.settle_rewards(platform_config)?;
+    source_agent.settle_position(source_position)?;
+    source_position.expire_lock(now);
+    platform_config.activate_stake(source_position, now)?;
+    target_agent.settle_rewards(platform_config)?;
+    target_agent.settle_position(target_position)?;
+    target_position.expire_lock(now);
+    platform_config.activate_stake(target_position, now)?;
+
+    // Only active stake moves, and it stays active; the user's and the platform's totals are unchanged
+    require!(amount <= source_position.active_amount(), ErrorCode::StakeNotActive);
+    source_position.amount = source_position.amount.checked_sub(amount).ok_or(ErrorCode::InvalidUnstakeAmount)?;
+    source_agent.staked_amount = source_agent.staked_amount.checked_sub(amount).ok_or(ErrorCode::InvalidUnstakeAmount)?;
+    target_position.amount = target_position.amount.checked_add(amount).ok_or(ErrorCode::InvalidStakeAmount)?;
+    target_agent.staked_amount = target_agent.staked_amount.checked_add(amount).ok_or(ErrorCode::InvalidStakeAmount)?;
+
+    let (old_weight, new_weight) = source_position.refresh_weight()?;
+    platform_config.apply_position_weight_change(source_agent, old_weight, new_weight)?;
+    source_position.reset_reward_debt(source_agent.acc_reward_per_share)?;
+    source_position.reset_slot_reward_debts(&source_agent.slot_acc_per_share)?;
+    let (old_weight, new_weight) = target_position.refresh_weight()?;
+    platform_config.apply_position_weight_change(target_agent, old_weight, new_weight)?;
+    target_position.reset_reward_debt(target_agent.acc_reward_per_share)?;
+    target_position.reset_slot_reward_debts(&target_agent.slot_acc_per_share)?;
+
+    if source_position.amount == 0 {
+        user_stake.position_count = user_stake.position_count.saturating_sub(1);
+        source_agent.position_count = source_agent.position_count.saturating_sub(1);
+    }
+
+    // Keep the moved stake exposed to the source agent for one unbonding period
+    let exposure_end = now.checked_add(platform_config.unbonding_period).ok_or(ErrorCode::InvalidUnbondingPeriod)?;
+    target_position.record_exposure(source_agent.key(), amount, source_agent.slash_count, exposure_end);
+    source_agent.outgoing_exposures = source_agent.outgoing_exposures.checked_add(1).ok_or(ErrorCode::InvalidStakeAmount)?;
+    source_position.last_redelegated_at = now;
+    target_position.last_redelegated_at = now;
+    user_stake.last_stake_update = now;
+
+    emit!(StakeRedelegated {
+        user: ctx.accounts.user.key(),
+        from_agent_id: source_agent.agent_id,
+        to_agent_id: target_agent.agent_id,
+        amount,
+        exposure_end,
+    });
+
+    msg!(
+        "User {} redelegated {} from agent {} to agent {}",
+        ctx.accounts.user.key(),
+        amount,
+        source_agent.agent_id,
+        target_agent.agent_id
+    );
+    Ok(())
+}
+
+// Release redelegated stake from its source agent's slashes once the exposure ran out (permissionless)
+#[derive(Accounts)]
+pub struct ReleaseExposure<'info> {
+    #[account(
+        mut,
+        seeds = [b"ai-agent", source_agent.owner.as_ref(), &source_agent.agent_id.to_le_bytes()],
+        bump = source_agent.bump,
+        constraint = !source_agent.slash_pending @ ErrorCode::AgentSlashPending
+    )]
+    pub source_agent: Account<'info, AiAgent>,
+    #[account(
+        mut,
+        seeds = [b"stake-position", stake_position.user.as_ref(), stake_position.agent.as_ref()],
+        bump = stake_position.bump,
+        constraint = stake_position.exposure_agent == source_agent.key() @ ErrorCode::InvalidRedelegation
+    )]
+    pub stake_position: Account<'info, StakePosition>,
+}
+
+pub fn release_exposure(ctx: Context<ReleaseExposure>) -> Result<()> {
+    let source_agent = &mut ctx.accounts.source_agent;
+    let stake_position = &mut ctx.accounts.stake_position;
+    let clock = Clock::get()?;
+
+    require!(clock.unix_timestamp >= stake_position.exposure_end, ErrorCode::StakeExposed);
+
+    stake_position.clear_exposure();
+    source_agent.outgoing_exposures = source_agent.outgoing_exposures.saturating_sub(1);
+
+    msg!("Position of {} released from agent {}", stake_position.user, source_agent.agent_id);
+    Ok(())
+}
+
+// Withdraw tokens from a matured unbonding ticket
+#[derive(Accounts)]
+#[instruction(ticket_id: u64)]
+pub struct WithdrawUnbonded<'info> {
+    #[account(
+        seeds = [b"platform-config"],
+        bump = platform_config.bump
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+    // Agent the ticket was unstaked from; its tickets stay put while a slash is pending
+    #[account(
+        mut,
+        seeds = [b"ai-agent", ai_agent.owner.as_ref(), &ai_agent.agent_id.to_le_bytes()],
+        bump = ai_agent.bump,
+        constraint = !ai_agent.slash_pending @ ErrorCode::AgentSlashPending
+    )]
+    pub ai_agent: Account<'info, AiAgent>,
+    #[account(
+        mut,
+        seeds = [b"unbonding-ticket", user.key().as_ref(), &ticket_id.to_le_bytes()],
+        bump = unbonding_ticket.bump,
+        has_one = user @ ErrorCode::Unauthorized,
+        constraint = unbonding_ticket.agent == ai_agent.key() @ ErrorCode::TicketAgentMismatch,
+        close = user
+    )]
+    pub unbonding_ticket: Account<'info, UnbondingTicket>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(mut)]
+    pub user_token_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"stake-vault"],
+        bump = platform_config.stake_vault_bump,
+        token::mint = platform_config.stake_mint,
+        token::authority = platform_config
+    )]
+    pub stake_vault: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn withdraw_unbonded(ctx: Context<WithdrawUnbonded>, ticket_id: u64) -> Result<()> {
+    let ticket = &ctx.accounts.unbonding_ticket;
+    let clock = Clock::get()?;
+
+    require!(ticket.is_mature(clock.unix_timestamp), ErrorCode::UnbondingNotComplete);
+    let ai_agent = &mut ctx.accounts.ai_agent;
+    ai_agent.unbonding_tickets = ai_agent.unbonding_tickets.saturating_sub(1);
+
+    // Transfer the unbonded tokens back to the user, signed by the platform config PDA
+    let seeds = &[b"platform-config".as_ref(), &[ctx.accounts.platform_config.bump]];
+    let signer = &[&seeds[..]];
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.stake_vault.to_account_info(),
+        to: ctx.accounts.user_token_account.to_account_info(),
+        authority: ctx.accounts.platform_config.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+    token::transfer(cpi_ctx, ticket.amount)?;
+
+    emit!(StakeWithdrawn {
+        user: ctx.accounts.user.key(),
+        agent_id: ticket.agent_id,
+        amount: ticket.amount,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("User {} withdrew {} from ticket {}", ctx.accounts.user.key(), ticket.amount, ticket_id);
+    Ok(())
+}
+
+// Claim accumulated rewards
+#[derive(Accounts)]
+pub struct ClaimStakeRewards<'info> {
+    #[account(
+        mut,
+        seeds = [b"platform-config"],
+        bump = platform_config.bump
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+    #[account(
+        mut,
+        seeds = [b"emission-schedule"],
+        bump = emission_schedule.bump
+    )]
+    pub emission_schedule: Account<'info, EmissionSchedule>,
+    #[account(
+        mut,
+        seeds = [b"ai-agent", ai_agent.owner.as_ref(), &ai_agent.agent_id.to_le_bytes()],
+        bump = ai_agent.bump
+    )]
+    pub ai_agent: Account<'info, AiAgent>,
+    #[account(
+        mut,
+        seeds = [b"stake-position", user.key().as_ref(), ai_agent.key().as_ref()],
+        bump = stake_position.bump,
+        has_one = user @ ErrorCode::Unauthorized
+    )]
+    pub stake_position: Account<'info, StakePosition>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(
+        mut,
+        constraint = user_token_account.mint == platform_config.reward_mint @ ErrorCode::InvalidRewardMint
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"reward-vault"],
+        bump = platform_config.reward_vault_bump
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+    // Required while vesting is enabled; the claimed reward is locked here instead of paid out
+    #[account(
+        mut,
+        has_one = user @ ErrorCode::Unauthorized
+    )]
+    pub vesting_escrow: Option<Account<'info, VestingEscrow>>,
+    pub token_program: Program<'info, Token>,
+}
+
+// Each active reward slot, in slot order, expects a (slot_vault, user_token_account) pair in remaining_accounts
+pub fn claim_rewards<'info>(ctx: Context<'_, '_, '_, 'info, ClaimStakeRewards<'info>>) -> Result<()> {
+    let platform_config = &mut ctx.accounts.platform_config;
+    let emission_schedule = &mut ctx.accounts.emission_schedule;
+    let ai_agent = &mut ctx.accounts.ai_agent;
+    let stake_position = &mut ctx.accounts.stake_position;
+    let clock = Clock::get()?;
+
+    // Bring the accumulator fully up to date and settle the position against it; the claim
+    // re-weights the position, so a backlog must not be credited at the new weight
+    platform_config.accrue_rewards_fully(emission_schedule, clock.unix_timestamp)?;
+    ai_agent.settle_rewards(platform_config)?;
+    ai_agent.settle_position(stake_position)?;
+
+    // Drop the lock bonus if the lock ran out since the last settlement, and activate
+    // whatever has warmed up since
+    stake_position.expire_lock(clock.unix_timestamp);
+    platform_config.activate_stake(stake_position, clock.unix_timestamp)?;
+    let (old_weight, new_weight) = stake_position.refresh_weight()?;
+    platform_config.apply_position_weight_change(ai_agent, old_weight, new_weight)?;
+    stake_position.reset_reward_debt(ai_agent.acc_reward_per_share)?;
+    stake_position.reset_slot_reward_debts(&ai_agent.slot_acc_per_share)?;
+
+    let pending = stake_position.pending_rewards;
+    if pending == 0 && stake_position.slot_pending_rewards.iter().all(|amount| *amount == 0) {
+        return err!(ErrorCode::NoRewardsToClaim);
+    }
+
+    // The owner's commission was already taken when the agent settled. An underfunded reward
+    // vault pays out what it holds beyond the vesting reservations and the rest stays pending,
+    // like an underfunded slot vault.
+    let reward_to_claim = stake_position.take_reward(platform_config.unreserved_rewards(ctx.accounts.reward_vault.amount));
+    if reward_to_claim < pending {
+        msg!("Reward vault underfunded; {} stays pending", pending - reward_to_claim);
+    }
+
+    // Record the claim and the claim timestamp for the position
+    ai_agent.record_reward_claim(reward_to_claim)?;
+    stake_position.last_reward_claim = clock.unix_timestamp;
+
+    // Lock the reward in the user's vesting escrow while vesting is enabled; the tokens stay
+    // in the reward vault, reserved until released. Otherwise pay out from the reward vault
+    // directly. A claim of slot rewards only has nothing to lock.
+    if reward_to_claim > 0 && platform_config.vesting_enabled() {
+        let vesting_escrow = ctx.accounts.vesting_escrow.as_mut().ok_or(ErrorCode::VestingEscrowRequired)?;
+        vesting_escrow.lock(
+            reward_to_claim,
+            clock.unix_timestamp,
+            platform_config.vesting_cliff,
+            platform_config.vesting_duration,
+        )?;
+        platform_config.reserve_vesting(reward_to_claim)?;
+    } else if reward_to_claim > 0 {
+        transfer_from_vault(
+            platform_config,
+            ctx.accounts.reward_vault.to_account_info(),
+            ctx.accounts.user_token_account.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            reward_to_claim,
+        )?;
+    }
+
+    // Pay out every partner reward mint from its own vault
+    let mut slot_accounts = ctx.remaining_accounts.chunks(2);
+    for (index, slot) in platform_config.reward_slots.iter().enumerate().filter(|(_, slot)| slot.is_active()) {
+        let pair = slot_accounts.next().ok_or(ErrorCode::MissingRewardSlotAccounts)?;
+        require!(pair.len() == 2, ErrorCode::MissingRewardSlotAccounts);
+        let slot_vault = Account::<TokenAccount>::try_from(&pair[0])?;
+        let slot_user_account = Account::<TokenAccount>::try_from(&pair[1])?;
+
+        let expected_vault = Pubkey::create_program_address(
+            &[b"reward-vault".as_ref(), slot.mint.as_ref(), &[slot.vault_bump]],
+            ctx.program_id,
+        )
+        .map_err(|_| ErrorCode::InvalidVault)?;
+        require_keys_eq!(slot_vault.key(), expected_vault, ErrorCode::InvalidVault);
+        require_keys_eq!(slot_user_account.mint, slot.mint, ErrorCode::InvalidRewardMint);
+
+        // An underfunded partner vault defers its own payout instead of failing the whole claim
+        let slot_reward = stake_position.take_slot_reward(index, slot_vault.amount);
+        if slot_reward == 0 {
+            if stake_position.slot_pending_rewards[index] > 0 {
+                msg!("Slot {} vault underfunded; {} stays pending", index, stake_position.slot_pending_rewards[index]);
+            }
+            continue;
+        }
+        ai_agent.record_slot_reward_claim(index, slot_reward)?;
+
+        transfer_from_vault(
+            platform_config,
+            slot_vault.to_account_info(),
+            slot_user_account.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            slot_reward,
+        )?;
+
+        emit!(SlotRewardClaimed {
+            user: ctx.accounts.user.key(),
+            agent_id: stake_position.agent_id,
+            mint: slot.mint,
+            reward_amount: slot_reward,
+            timestamp: clock.unix_timestamp,
+        });
+    }
+
+    emit!(RewardClaimed {
+        user: ctx.accounts.user.key(),
+        agent_id: stake_position.agent_id,
+        reward_amount: reward_to_claim,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("User {} claimed rewards on agent {}: {}", ctx.accounts.user.key(), stake_position.agent_id, reward_to_claim);
+    Ok(())
+}
+
+// Transfer tokens out of a platform-owned vault, signed by the platform config PDA
+pub(crate) fn transfer_from_vault<'info>(
+    platform_config: &Account<'info, PlatformConfig>,
+    vault: AccountInfo<'info>,
+    destination: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    let seeds = &[b"platform-config".as_ref(), &[platform_config.bump]];
+    let signer = &[&seeds[..]];
+    let cpi_accounts = Transfer {
+        from: vault,
+        to: destination,
+        authority: platform_config.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(token_program, cpi_accounts, signer);
+    token::transfer(cpi_ctx, amount)
+}
+
+// Claim commission earned by an agent owner on delegators' rewards
+#[derive(Accounts)]
+pub struct ClaimCommission<'info> {
+    #[account(
+        seeds = [b"platform-config"],
+        bump = platform_config.bump
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+    #[account(
+        mut,
+        seeds = [b"ai-agent", ai_agent.owner.as_ref(), &ai_agent.agent_id.to_le_bytes()],
+        bump = ai_agent.bump,
+        constraint = ai_agent.is_controlled_by(&owner.key(), owner_nft_account.as_deref()) @ ErrorCode::Unauthorized
+    )]
+    pub ai_agent: Account<'info, AiAgent>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    // Required once the agent is tokenized: the signer's token account holding the ownership NFT
+    pub owner_nft_account: Option<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = owner_token_account.mint == platform_config.reward_mint @ ErrorCode::InvalidRewardMint
+    )]
+    pub owner_token_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"reward-vault"],
+        bump = platform_config.reward_vault_bump
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+// Each active reward slot, in slot order, expects a (slot_vault, owner_token_account) pair in remaining_accounts
+pub fn claim_commission<'info>(ctx: Context<'_, '_, '_, 'info, ClaimCommission<'info>>) -> Result<()> {
+    let platform_config = &ctx.accounts.platform_config;
+    let ai_agent = &mut ctx.accounts.ai_agent;
+    let clock = Clock::get()?;
+
+    if ai_agent.accrued_commission == 0 && ai_agent.accrued_slot_commission.iter().all(|amount| *amount == 0) {
+        return err!(ErrorCode::NoRewardsToClaim);
+    }
+
+    // Rewards reserved for vesting escrows are not available; what the vault cannot cover stays accrued
+    let commission = ai_agent
+        .accrued_commission
+        .min(platform_config.unreserved_rewards(ctx.accounts.reward_vault.amount));
+    ai_agent.accrued_commission -= commission;
+
+    // Transfer the commission from the reward vault
+    if commission > 0 {
+        transfer_from_vault(
+            platform_config,
+            ctx.accounts.reward_vault.to_account_info(),
+            ctx.accounts.owner_token_account.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            commission,
+        )?;
+    }
+
+    // Commission on partner reward mints is paid from each slot's own vault
+    let mut slot_accounts = ctx.remaining_accounts.chunks(2);
+    for (index, slot) in platform_config.reward_slots.iter().enumerate().filter(|(_, slot)| slot.is_active()) {
+        let pair = slot_accounts.next().ok_or(ErrorCode::MissingRewardSlotAccounts)?;
+        require!(pair.len() == 2, ErrorCode::MissingRewardSlotAccounts);
+        let slot_vault = Account::<TokenAccount>::try_from(&pair[0])?;
+        let slot_owner_account = Account::<TokenAccount>::try_from(&pair[1])?;
+
+        let expected_vault = Pubkey::create_program_address(
+            &[b"reward-vault".as_ref(), slot.mint.as_ref(), &[slot.vault_bump]],
+            ctx.program_id,
+        )
+        .map_err(|_| ErrorCode::InvalidVault)?;
+        require_keys_eq!(slot_vault.key(), expected_vault, ErrorCode::InvalidVault);
+        require_keys_eq!(slot_owner_account.mint, slot.mint, ErrorCode::InvalidRewardMint);
+
+        let slot_commission = ai_agent.accrued_slot_commission[index];
+        if slot_commission == 0 {
+            continue;
+        }
+        ai_agent.accrued_slot_commission[index] = 0;
+
+        transfer_from_vault(
+            platform_config,
+            slot_vault.to_account_info(),
+            slot_owner_account.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            slot_commission,
+        )?;
+    }
+
+    emit!(CommissionClaimed {
+        owner: ctx.accounts.owner.key(),
+        agent_id: ai_agent.agent_id,
+        amount: commission,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Owner {} claimed commission on agent {}: {}", ctx.accounts.owner.key(), ai_agent.agent_id, commission);
     Ok(())
 }
 
@@ -324,4 +2270,76 @@ pub enum ErrorCode {
     MetadataTooLarge,
     #[msg("No rewards available to claim.")]
     NoRewardsToClaim,
+    #[msg("Unstake amount exceeds staked balance.")]
+    InvalidUnstakeAmount,
+    #[msg("Unbonding period has not ended yet.")]
+    UnbondingNotComplete,
+    #[msg("Invalid unbonding period.")]
+    InvalidUnbondingPeriod,
+    #[msg("Vault is not owned by the platform.")]
+    InvalidVault,
+    #[msg("Stake is still locked.")]
+    StakeLocked,
+    #[msg("Commission must not exceed 100%.")]
+    InvalidCommission,
+    #[msg("Token account mint does not match the reward mint.")]
+    InvalidRewardMint,
+    #[msg("Stake and performance reward weights must add up to 10000 bps.")]
+    InvalidRewardWeights,
+    #[msg("Supply cap is below the amount already emitted.")]
+    InvalidSupplyCap,
+    #[msg("All reward slots are in use.")]
+    RewardSlotsFull,
+    #[msg("Reward slot is not active.")]
+    InvalidRewardSlot,
+    #[msg("Reward mint is already configured.")]
+    DuplicateRewardMint,
+    #[msg("Missing vault or token account for an active reward slot.")]
+    MissingRewardSlotAccounts,
+    #[msg("Invalid vesting schedule.")]
+    InvalidVestingSchedule,
+    #[msg("Vesting is enabled; a vesting escrow must be provided.")]
+    VestingEscrowRequired,
+    #[msg("Agent is not accepting stake.")]
+    AgentNotActive,
+    #[msg("Agent is not retiring.")]
+    AgentNotRetiring,
+    #[msg("Agent must be retired with no stake or unclaimed rewards.")]
+    AgentNotRetired,
+    #[msg("Evolution interval must not be negative.")]
+    InvalidEvolutionInterval,
+    #[msg("Operator must be a key other than the owner.")]
+    InvalidOperator,
+    #[msg("Liveness window and jail duration must not be negative.")]
+    InvalidLivenessConfig,
+    #[msg("Agent is jailed.")]
+    AgentJailed,
+    #[msg("Agent is not jailed.")]
+    AgentNotJailed,
+    #[msg("Agent has not missed the liveness window.")]
+    AgentStillLive,
+    #[msg("Agent must serve the minimum jail duration and send a heartbeat before it can be unjailed.")]
+    UnjailTooSoon,
+    #[msg("A slash against this agent is pending.")]
+    AgentSlashPending,
+    #[msg("Slash dispute and resolution windows must not be negative and the slash cap must not exceed 100%.")]
+    InvalidSlashingConfig,
+    #[msg("Stake is still exposed to slashes on the agent it was redelegated from.")]
+    StakeExposed,
+    #[msg("Position was redelegated too recently.")]
+    RedelegationCooldown,
+    #[msg("Cannot redelegate to the same agent.")]
+    InvalidRedelegation,
+    #[msg("Redelegation cooldown must not be negative.")]
+    InvalidRedelegationCooldown,
+    #[msg("Stake has not finished activating.")]
+    StakeNotActive,
+    #[msg("Activation cap must not exceed 100%.")]
+    InvalidActivationConfig,
+    #[msg("Position has no expired lock bonus to drop.")]
+    NoExpiredLock,
+    #[msg("Epoch duration cannot change once emission has started.")]
+    EpochDurationLocked,
+    #[msg("Unbonding ticket was not unstaked from this agent.")]
+    TicketAgentMismatch,
 }
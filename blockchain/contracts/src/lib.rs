@@ -2,6 +2,25 @@ use anchor_lang::prelude::*;
 use anchor_lang::solana_program::clock::Clock;
 use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
 
+pub mod events;
+use events::{RewardDistributed, RewardPayoutDegraded};
+
+pub mod state;
+use state::{AgentMetadataParams, AgentStatus, EmissionCurve};
+pub mod instructions;
+pub mod distributor;
+pub mod vesting;
+pub mod agent_nft;
+pub mod slashing;
+pub mod governance;
+pub mod error;
+use distributor::*;
+use vesting::*;
+use agent_nft::*;
+use instructions::*;
+use slashing::*;
+use governance::*;
+
 // Declare the program ID for the smart contract
 declare_id!("YourProgramIDHere"); // Replace with your actual program ID after deployment
 
@@ -9,6 +28,8 @@ declare_id!("YourProgramIDHere"); // Replace with your actual program ID after d
 const STAKING_COOLDOWN: i64 = 86400; // 24 hours in seconds for unstaking cooldown
 const REWARD_RATE: u64 = 100; // Reward rate per epoch (adjustable)
 const EPOCH_DURATION: i64 = 604800; // 7 days in seconds for reward epoch
+const KEEPER_TIP_BPS: u64 = 50; // 0.5% of each reward paid by the crank goes to the caller
+const MAX_CRANK_BATCH: usize = 20; // Maximum agents processed per crank call
 
 // Custom error codes for the program
 #[error_code]
@@ -25,6 +46,16 @@ pub enum FabeonError {
     InsufficientVotingPower,
     #[msg("Reward pool depleted")]
     RewardPoolDepleted,
+    #[msg("Too many agents in one crank call")]
+    CrankBatchTooLarge,
+    #[msg("No agent in the batch was due a reward")]
+    NothingToDistribute,
+    #[msg("Account is not an AI agent of this program")]
+    InvalidAgentAccount,
+    #[msg("Reward vault is not owned by the reward pool")]
+    InvalidRewardVault,
+    #[msg("Arithmetic overflow")]
+    MathOverflow,
 }
 
 // Account structure for an AI Agent
@@ -35,9 +66,59 @@ pub struct AIAgent {
     pub last_stake_time: i64, // Timestamp of last staking action
     pub accumulated_rewards: u64, // Accumulated rewards for this agent
     pub is_active: bool, // Whether the agent is active
+    pub last_rewarded_epoch: u64, // Last reward epoch this agent was paid for
+    pub reward_debt: u64, // Rewards owed from epochs the pool could not fully cover
     pub bump: u8, // Bump seed for PDA derivation
 }
 
+impl AIAgent {
+    // Credit this epoch's reward if the agent has not been paid for it yet. When the pool is
+    // short, only the epoch's pro-rata share is paid and the rest is recorded as debt.
+    // Returns the amount credited (before any keeper tip), or None if not due.
+    pub fn take_epoch_reward(&mut self, reward_pool: &mut RewardPool) -> Result<Option<u64>> {
+        if !self.is_active || self.last_rewarded_epoch >= reward_pool.current_epoch {
+            return Ok(None);
+        }
+        let reward = self.staked_amount
+            .checked_mul(REWARD_RATE)
+            .map(|v| v / 1000) // Example: 0.1% of staked amount per epoch
+            .ok_or(FabeonError::MathOverflow)?;
+        let paid = (reward as u128)
+            .checked_mul(reward_pool.payout_ratio_bps as u128)
+            .map(|v| (v / 10_000) as u64)
+            .ok_or(FabeonError::MathOverflow)?
+            .min(reward_pool.total_rewards);
+        let shortfall = reward.checked_sub(paid).ok_or(FabeonError::MathOverflow)?;
+
+        reward_pool.total_rewards = reward_pool.total_rewards.checked_sub(paid).ok_or(FabeonError::MathOverflow)?;
+        reward_pool.outstanding_debt = reward_pool.outstanding_debt
+            .checked_add(shortfall)
+            .ok_or(FabeonError::MathOverflow)?;
+        self.reward_debt = self.reward_debt.checked_add(shortfall).ok_or(FabeonError::MathOverflow)?;
+        self.accumulated_rewards = self.accumulated_rewards.checked_add(paid).ok_or(FabeonError::MathOverflow)?;
+        self.last_rewarded_epoch = reward_pool.current_epoch;
+        Ok(Some(paid))
+    }
+
+    // Pay down this agent's debt from funds reserved by top-ups. Returns the amount repaid.
+    pub fn settle_debt(&mut self, reward_pool: &mut RewardPool) -> Result<u64> {
+        let repaid = self.reward_debt.min(reward_pool.debt_reserve);
+        self.reward_debt -= repaid;
+        self.accumulated_rewards = self.accumulated_rewards.checked_add(repaid).ok_or(FabeonError::MathOverflow)?;
+        reward_pool.debt_reserve -= repaid;
+        reward_pool.outstanding_debt = reward_pool.outstanding_debt
+            .checked_sub(repaid)
+            .ok_or(FabeonError::MathOverflow)?;
+        Ok(repaid)
+    }
+}
+
+// Share of a crank-paid reward that goes to the keeper
+pub fn keeper_tip(reward: u64) -> Result<u64> {
+    let tip = reward.checked_mul(KEEPER_TIP_BPS).ok_or(FabeonError::MathOverflow)? / 10_000;
+    Ok(tip)
+}
+
 // Account structure for Governance Proposal
 #[account]
 pub struct GovernanceProposal {
@@ -56,9 +137,61 @@ pub struct GovernanceProposal {
 pub struct RewardPool {
     pub total_rewards: u64, // Total rewards available in the pool
     pub last_updated: i64, // Last time the pool was updated
+    pub current_epoch: u64, // Reward epoch agents are currently paid for
+    pub epoch_start: i64, // Start time of the current epoch
+    pub total_staked: u64, // Stake of all active agents, used to size each epoch's rewards
+    pub payout_ratio_bps: u64, // Share of each reward the pool covers this epoch (10000 = in full)
+    pub outstanding_debt: u64, // Rewards owed to agents from underfunded epochs
+    pub debt_reserve: u64, // Top-up funds set aside to repay outstanding debt
     pub bump: u8, // Bump seed for PDA derivation
 }
 
+impl RewardPool {
+    // Credit a top-up. Outstanding debt from underfunded epochs is reserved first; the rest
+    // funds future epochs.
+    pub fn add_funds(&mut self, amount: u64) -> Result<()> {
+        let unreserved_debt = self.outstanding_debt.checked_sub(self.debt_reserve).ok_or(FabeonError::MathOverflow)?;
+        let to_debt = amount.min(unreserved_debt);
+        self.debt_reserve = self.debt_reserve.checked_add(to_debt).ok_or(FabeonError::MathOverflow)?;
+        self.total_rewards = self.total_rewards.checked_add(amount - to_debt).ok_or(FabeonError::MathOverflow)?;
+        Ok(())
+    }
+
+    // Move to the latest epoch once the current one has run its full duration, and fix the
+    // share of each reward the pool can cover for the new epoch. Returns an event to emit
+    // when that share is below 100%.
+    pub fn advance_epoch(&mut self, current_time: i64) -> Result<Option<RewardPayoutDegraded>> {
+        let elapsed = current_time.checked_sub(self.epoch_start).ok_or(FabeonError::MathOverflow)?;
+        self.last_updated = current_time;
+        if elapsed < EPOCH_DURATION {
+            return Ok(None);
+        }
+        let epochs = elapsed / EPOCH_DURATION;
+        self.current_epoch = self.current_epoch.checked_add(epochs as u64).ok_or(FabeonError::MathOverflow)?;
+        self.epoch_start = epochs
+            .checked_mul(EPOCH_DURATION)
+            .and_then(|v| self.epoch_start.checked_add(v))
+            .ok_or(FabeonError::MathOverflow)?;
+
+        let required = self.total_staked
+            .checked_mul(REWARD_RATE)
+            .map(|v| v / 1000)
+            .ok_or(FabeonError::MathOverflow)?;
+        if required <= self.total_rewards {
+            self.payout_ratio_bps = 10_000;
+            return Ok(None);
+        }
+        self.payout_ratio_bps = (self.total_rewards as u128 * 10_000 / required as u128) as u64;
+        Ok(Some(RewardPayoutDegraded {
+            epoch: self.current_epoch,
+            payout_ratio_bps: self.payout_ratio_bps,
+            available: self.total_rewards,
+            required,
+            timestamp: current_time,
+        }))
+    }
+}
+
 // Program entrypoint and instructions
 #[program]
 pub mod Omelix_ai {
@@ -69,6 +202,12 @@ pub mod Omelix_ai {
         let reward_pool = &mut ctx.accounts.reward_pool;
         reward_pool.total_rewards = initial_rewards;
         reward_pool.last_updated = Clock::get()?.unix_timestamp;
+        reward_pool.current_epoch = 0;
+        reward_pool.epoch_start = reward_pool.last_updated;
+        reward_pool.total_staked = 0;
+        reward_pool.payout_ratio_bps = 10_000;
+        reward_pool.outstanding_debt = 0;
+        reward_pool.debt_reserve = 0;
         reward_pool.bump = ctx.bumps.reward_pool;
         Ok(())
     }
@@ -99,8 +238,18 @@ pub mod Omelix_ai {
         ai_agent.last_stake_time = Clock::get()?.unix_timestamp;
         ai_agent.accumulated_rewards = 0;
         ai_agent.is_active = true;
+        ai_agent.reward_debt = 0;
         ai_agent.bump = ctx.bumps.ai_agent;
 
+        // Bring the pool to the current epoch first, so the agent is only paid for epochs that
+        // start after it registered
+        let reward_pool = &mut ctx.accounts.reward_pool;
+        if let Some(degraded) = reward_pool.advance_epoch(ai_agent.last_stake_time)? {
+            emit!(degraded);
+        }
+        ai_agent.last_rewarded_epoch = reward_pool.current_epoch;
+        reward_pool.total_staked = reward_pool.total_staked.checked_add(stake_amount).ok_or(FabeonError::MathOverflow)?;
+
         Ok(())
     }
 
@@ -134,6 +283,11 @@ pub mod Omelix_ai {
         token::transfer(cpi_ctx, ai_agent.staked_amount)?;
 
         // Update AI agent state
+        if ai_agent.is_active {
+            ctx.accounts.reward_pool.total_staked = ctx.accounts.reward_pool.total_staked
+                .checked_sub(ai_agent.staked_amount)
+                .ok_or(FabeonError::MathOverflow)?;
+        }
         ai_agent.staked_amount = 0;
         ai_agent.is_active = false;
         ai_agent.last_stake_time = current_time;
@@ -141,26 +295,80 @@ pub mod Omelix_ai {
         Ok(())
     }
 
-    // Distribute rewards to AI agents
+    // Distribute the current epoch's reward to a single AI agent
     pub fn distribute_rewards(ctx: Context<DistributeRewards>) -> Result<()> {
         let reward_pool = &mut ctx.accounts.reward_pool;
         let ai_agent = &mut ctx.accounts.ai_agent;
         let current_time = Clock::get()?.unix_timestamp;
 
-        // Check if enough time has passed since last update (epoch duration)
-        require!(
-            current_time >= reward_pool.last_updated + EPOCH_DURATION,
-            OntoraError::CooldownNotCompleted
-        );
+        // Each agent is paid at most once per epoch; any debt is repaid first
+        if let Some(degraded) = reward_pool.advance_epoch(current_time)? {
+            emit!(degraded);
+        }
+        let repaid = ai_agent.settle_debt(reward_pool)?;
+        let paid = ai_agent.take_epoch_reward(reward_pool)?;
+        require!(paid.is_some() || repaid > 0, FabeonError::CooldownNotCompleted);
+
+        Ok(())
+    }
+
+    // Permissionless crank: pay the current epoch's reward to a page of agents passed as
+    // remaining_accounts. Agents already paid this epoch are skipped, and the caller earns
+    // KEEPER_TIP_BPS of every reward paid.
+    pub fn crank_rewards<'info>(ctx: Context<'_, '_, '_, 'info, CrankRewards<'info>>) -> Result<()> {
+        let reward_pool = &mut ctx.accounts.reward_pool;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(ctx.remaining_accounts.len() <= MAX_CRANK_BATCH, FabeonError::CrankBatchTooLarge);
+        if let Some(degraded) = reward_pool.advance_epoch(current_time)? {
+            emit!(degraded);
+        }
 
-        // Calculate rewards based on staked amount and reward rate
-        let reward = ai_agent.staked_amount * REWARD_RATE / 1000; // Example: 0.1% of staked amount per epoch
-        require!(reward_pool.total_rewards >= reward, OntoraError::RewardPoolDepleted);
+        let mut total_amount: u64 = 0;
+        let mut total_repaid: u64 = 0;
+        let mut total_tip: u64 = 0;
+        let mut eligible_count: u64 = 0;
+        for agent_info in ctx.remaining_accounts.iter() {
+            require!(agent_info.is_writable, FabeonError::InvalidAgentAccount);
+            let mut ai_agent = Account::<AIAgent>::try_from(agent_info)
+                .map_err(|_| FabeonError::InvalidAgentAccount)?;
+
+            let repaid = ai_agent.settle_debt(reward_pool)?;
+            total_repaid = total_repaid.checked_add(repaid).ok_or(FabeonError::MathOverflow)?;
+            if let Some(reward) = ai_agent.take_epoch_reward(reward_pool)? {
+                let tip = keeper_tip(reward)?;
+                ai_agent.accumulated_rewards = ai_agent.accumulated_rewards
+                    .checked_sub(tip)
+                    .ok_or(FabeonError::MathOverflow)?;
+                total_amount = total_amount.checked_add(reward).ok_or(FabeonError::MathOverflow)?;
+                total_tip = total_tip.checked_add(tip).ok_or(FabeonError::MathOverflow)?;
+                eligible_count += 1;
+            }
+            // Persist right away so a duplicate later in the page reads the updated epoch
+            ai_agent.exit(ctx.program_id)?;
+        }
+        require!(eligible_count > 0 || total_repaid > 0, FabeonError::NothingToDistribute);
+
+        // Pay the keeper tip out of the reward vault
+        if total_tip > 0 {
+            let seeds = &[b"reward_pool".as_ref(), &[reward_pool.bump]];
+            let signer = &[&seeds[..]];
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.reward_vault.to_account_info(),
+                to: ctx.accounts.keeper_token_account.to_account_info(),
+                authority: reward_pool.to_account_info(),
+            };
+            let cpi_program = ctx.accounts.token_program.to_account_info();
+            let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+            token::transfer(cpi_ctx, total_tip)?;
+        }
 
-        // Update reward pool and agent rewards
-        reward_pool.total_rewards -= reward;
-        ai_agent.accumulated_rewards += reward;
-        reward_pool.last_updated = current_time;
+        emit!(RewardDistributed {
+            authority: ctx.accounts.keeper.key(),
+            timestamp: current_time,
+            total_amount: total_amount.checked_add(total_repaid).ok_or(FabeonError::MathOverflow)?,
+            eligible_count,
+        });
 
         Ok(())
     }
@@ -175,11 +383,14 @@ pub mod Omelix_ai {
         // Check if the caller is the owner
         require!(ai_agent.owner == ctx.accounts.user.key(), OntoraError::InvalidOwner);
 
+        // Collect any debt the pool can now repay
+        ai_agent.settle_debt(&mut ctx.accounts.reward_pool)?;
+
         // Check if there are rewards to claim
         require!(ai_agent.accumulated_rewards > 0, OntoraError::RewardPoolDepleted);
 
         // Transfer rewards from vault to user
-        let seeds = &[b"reward_vault", &[ctx.accounts.reward_pool.bump]];
+        let seeds = &[b"reward_pool".as_ref(), &[ctx.accounts.reward_pool.bump]];
         let signer = &[&seeds[..]];
         let cpi_accounts = Transfer {
             from: reward_vault.to_account_info(),
@@ -196,6 +407,28 @@ pub mod Omelix_ai {
         Ok(())
     }
 
+    // Add rewards to the pool. Outstanding debt from underfunded epochs is covered first;
+    // agents collect their share of it on their next distribution or claim.
+    pub fn top_up_reward_pool(ctx: Context<TopUpRewardPool>, amount: u64) -> Result<()> {
+        let reward_pool = &mut ctx.accounts.reward_pool;
+
+        require!(amount > 0, FabeonError::InsufficientStake);
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.funder_token_account.to_account_info(),
+            to: ctx.accounts.reward_vault.to_account_info(),
+            authority: ctx.accounts.funder.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+        token::transfer(cpi_ctx, amount)?;
+
+        reward_pool.add_funds(amount)?;
+        reward_pool.last_updated = Clock::get()?.unix_timestamp;
+
+        Ok(())
+    }
+
     // Create a governance proposal
     pub fn create_proposal(ctx: Context<CreateProposal>, description: String, duration: i64) -> Result<()> {
         let proposal = &mut ctx.accounts.proposal;
@@ -243,12 +476,416 @@ pub mod Omelix_ai {
 
         Ok(())
     }
+
+    // Initialize the platform configuration
+    pub fn initialize_platform(
+        ctx: Context<InitializePlatform>,
+        min_stake_amount: u64,
+        epoch_duration: i64,
+        unbonding_period: i64,
+    ) -> Result<()> {
+        instructions::initialize_platform(ctx, min_stake_amount, epoch_duration, unbonding_period)
+    }
+
+    // Update platform configuration (admin only)
+    pub fn update_platform_config(
+        ctx: Context<UpdatePlatformConfig>,
+        min_stake_amount: u64,
+        epoch_duration: i64,
+        unbonding_period: i64,
+    ) -> Result<()> {
+        instructions::update_platform_config(ctx, min_stake_amount, epoch_duration, unbonding_period)
+    }
+
+    // Set how emissions are split between stake and performance, and who scores agents (admin only)
+    pub fn update_reward_distribution(
+        ctx: Context<UpdatePlatformConfig>,
+        stake_weight_bps: u64,
+        performance_weight_bps: u64,
+        evaluator: Pubkey,
+    ) -> Result<()> {
+        instructions::update_reward_distribution(ctx, stake_weight_bps, performance_weight_bps, evaluator)
+    }
+
+    // Configure vesting of claimed rewards (admin only)
+    pub fn update_vesting_config(
+        ctx: Context<UpdatePlatformConfig>,
+        vesting_cliff: i64,
+        vesting_duration: i64,
+        early_exit_penalty_bps: u64,
+    ) -> Result<()> {
+        instructions::update_vesting_config(ctx, vesting_cliff, vesting_duration, early_exit_penalty_bps)
+    }
+
+    // Set the cap on agent commission and the notice period for increases (admin only)
+    pub fn update_commission_policy(
+        ctx: Context<UpdatePlatformConfig>,
+        max_commission_bps: u64,
+        commission_notice_period: i64,
+    ) -> Result<()> {
+        instructions::update_commission_policy(ctx, max_commission_bps, commission_notice_period)
+    }
+
+    // Set the minimum interval between agent evolutions (admin only)
+    pub fn update_evolution_interval(ctx: Context<UpdatePlatformConfig>, min_evolution_interval: i64) -> Result<()> {
+        instructions::update_evolution_interval(ctx, min_evolution_interval)
+    }
+
+    // Set the agent liveness window and minimum jail duration (admin only)
+    pub fn update_liveness_config(
+        ctx: Context<UpdatePlatformConfig>,
+        liveness_window: i64,
+        min_jail_duration: i64,
+    ) -> Result<()> {
+        instructions::update_liveness_config(ctx, liveness_window, min_jail_duration)
+    }
+
+    // Set stake warmup, cooldown and the per-epoch activation cap (admin only)
+    pub fn update_activation_config(
+        ctx: Context<UpdatePlatformConfig>,
+        warmup_epochs: u64,
+        cooldown_epochs: u64,
+        activation_cap_bps: u64,
+    ) -> Result<()> {
+        instructions::update_activation_config(ctx, warmup_epochs, cooldown_epochs, activation_cap_bps)
+    }
+
+    // Set the minimum interval between redelegations of a position (admin only)
+    pub fn update_redelegation_cooldown(ctx: Context<UpdatePlatformConfig>, redelegation_cooldown: i64) -> Result<()> {
+        instructions::update_redelegation_cooldown(ctx, redelegation_cooldown)
+    }
+
+    // Switch staker governance on or off (admin only)
+    pub fn update_governance_config(ctx: Context<UpdatePlatformConfig>, governance_enabled: bool) -> Result<()> {
+        instructions::update_governance_config(ctx, governance_enabled)
+    }
+
+    // Set the slash treasury, appeal windows and per-slash cap (admin only)
+    pub fn update_slashing_config(
+        ctx: Context<UpdatePlatformConfig>,
+        slash_treasury: Pubkey,
+        slash_dispute_window: i64,
+        slash_resolution_window: i64,
+        max_slash_bps: u64,
+    ) -> Result<()> {
+        instructions::update_slashing_config(
+            ctx,
+            slash_treasury,
+            slash_dispute_window,
+            slash_resolution_window,
+            max_slash_bps,
+        )
+    }
+
+    // Create the reward emission schedule (admin only)
+    pub fn initialize_emission_schedule(
+        ctx: Context<InitializeEmissionSchedule>,
+        curve: EmissionCurve,
+        start_timestamp: i64,
+        supply_cap: u64,
+    ) -> Result<()> {
+        instructions::initialize_emission_schedule(ctx, curve, start_timestamp, supply_cap)
+    }
+
+    // Replace the emission curve and supply cap (admin only)
+    pub fn update_emission_schedule(
+        ctx: Context<UpdatePlatformConfig>,
+        curve: EmissionCurve,
+        supply_cap: u64,
+    ) -> Result<()> {
+        instructions::update_emission_schedule(ctx, curve, supply_cap)
+    }
+
+    // Accrue emissions up to now, a bounded number of epochs per call (permissionless)
+    pub fn accrue_rewards(ctx: Context<AccrueRewards>) -> Result<()> {
+        instructions::accrue_rewards(ctx)
+    }
+
+    // Create the program-owned reward vault (admin only)
+    pub fn initialize_reward_vault(ctx: Context<InitializeRewardVault>) -> Result<()> {
+        instructions::initialize_reward_vault(ctx)
+    }
+
+    // Create the program-owned vault holding staked tokens (admin only)
+    pub fn initialize_stake_vault(ctx: Context<InitializeStakeVault>) -> Result<()> {
+        instructions::initialize_stake_vault(ctx)
+    }
+
+    // Deposit reward liquidity into the reward vault
+    pub fn fund_reward_vault(ctx: Context<FundRewardVault>, amount: u64) -> Result<()> {
+        instructions::fund_reward_vault(ctx, amount)
+    }
+
+    // Add a partner reward mint with its own vault (admin only)
+    pub fn add_reward_slot(ctx: Context<AddRewardSlot>, rate_per_epoch: u64) -> Result<()> {
+        instructions::add_reward_slot(ctx, rate_per_epoch)
+    }
+
+    // Change the emission rate of a partner reward mint (admin only)
+    pub fn update_reward_slot(ctx: Context<UpdatePlatformConfig>, index: u8, rate_per_epoch: u64) -> Result<()> {
+        instructions::update_reward_slot(ctx, index, rate_per_epoch)
+    }
+
+    // Deposit partner reward liquidity into a slot's vault
+    pub fn fund_reward_slot(ctx: Context<FundRewardSlot>, index: u8, amount: u64) -> Result<()> {
+        instructions::fund_reward_slot(ctx, index, amount)
+    }
+
+    // Change an agent's commission (owner only)
+    pub fn set_commission(ctx: Context<SetCommission>, commission_bps: u64) -> Result<()> {
+        instructions::set_commission(ctx, commission_bps)
+    }
+
+    // Assign, rotate or revoke an agent's operator key (owner only)
+    pub fn set_operator(ctx: Context<SetOperator>, operator: Option<Pubkey>) -> Result<()> {
+        instructions::set_operator(ctx, operator)
+    }
+
+    // Register a new AI agent under the next sequential ID
+    pub fn register_agent(
+        ctx: Context<RegisterAgent>,
+        name: String,
+        description: String,
+        commission_bps: u64,
+    ) -> Result<()> {
+        instructions::register_ai_agent(ctx, name, description, commission_bps)
+    }
+
+    // Update an agent's name and description (owner only)
+    pub fn update_ai_agent(ctx: Context<UpdateAiAgent>, name: String, description: String) -> Result<()> {
+        instructions::update_ai_agent(ctx, name, description)
+    }
+
+    // Publish an agent's typed metadata (owner only)
+    pub fn create_agent_metadata(ctx: Context<CreateAgentMetadata>, params: AgentMetadataParams) -> Result<()> {
+        instructions::create_agent_metadata(ctx, params)
+    }
+
+    // Replace an agent's typed metadata (owner only)
+    pub fn update_agent_metadata(ctx: Context<UpdateAgentMetadata>, params: AgentMetadataParams) -> Result<()> {
+        instructions::update_agent_metadata(ctx, params)
+    }
+
+    // Move an agent through its lifecycle (owner only)
+    pub fn set_agent_status(ctx: Context<SetAgentStatus>, status: AgentStatus) -> Result<()> {
+        instructions::set_agent_status(ctx, status)
+    }
+
+    // Close a retired agent and return its rent to the owner
+    pub fn close_agent(ctx: Context<CloseAgent>) -> Result<()> {
+        instructions::close_agent(ctx)
+    }
+
+    // Record a new evolution chapter for an agent (owner or operator)
+    pub fn evolve_ai_agent(
+        ctx: Context<EvolveAiAgent>,
+        behavior_hash: [u8; 32],
+        model_hash: [u8; 32],
+        model_version: u16,
+        chapter_id: u32,
+        interaction_count: u64,
+    ) -> Result<()> {
+        instructions::evolve_ai_agent(ctx, behavior_hash, model_hash, model_version, chapter_id, interaction_count)
+    }
+
+    // Report that an agent is alive (owner or operator)
+    pub fn heartbeat(ctx: Context<Heartbeat>) -> Result<()> {
+        instructions::heartbeat(ctx)
+    }
+
+    // Jail an agent that missed the liveness window (permissionless)
+    pub fn jail_agent(ctx: Context<JailAgent>) -> Result<()> {
+        instructions::jail_agent(ctx)
+    }
+
+    // Release a jailed agent once it is live again (owner only)
+    pub fn unjail(ctx: Context<UnjailAgent>) -> Result<()> {
+        instructions::unjail(ctx)
+    }
+
+    // Update an agent's performance score (evaluator only)
+    pub fn update_performance_score(ctx: Context<UpdatePerformanceScore>, performance_score: u64) -> Result<()> {
+        instructions::update_performance_score(ctx, performance_score)
+    }
+
+    // Stake (delegate) tokens on any registered AI agent
+    pub fn stake_on_agent(
+        ctx: Context<StakeOnAgent>,
+        agent_owner: Pubkey,
+        agent_id: u64,
+        amount: u64,
+        lock_duration: i64,
+    ) -> Result<()> {
+        instructions::stake_on_agent(ctx, agent_owner, agent_id, amount, lock_duration)
+    }
+
+    // Unstake tokens from an AI agent into an unbonding ticket
+    pub fn unstake_from_agent(
+        ctx: Context<UnstakeFromAgent>,
+        agent_owner: Pubkey,
+        agent_id: u64,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::unstake_from_agent(ctx, agent_owner, agent_id, amount)
+    }
+
+    // Unbond a delegator's whole position from a retiring agent (permissionless)
+    pub fn force_unbond(ctx: Context<ForceUnbond>) -> Result<()> {
+        instructions::force_unbond(ctx)
+    }
+
+    // Drop the bonus of a position whose lock has run out (permissionless)
+    pub fn refresh_position(ctx: Context<RefreshPosition>) -> Result<()> {
+        instructions::refresh_position(ctx)
+    }
+
+    // Activate a position's warmed-up stake within this epoch's activation budget (permissionless)
+    pub fn activate_stake(ctx: Context<ActivateStake>) -> Result<()> {
+        instructions::activate_stake(ctx)
+    }
+
+    // Move stake from one agent to another without unbonding
+    pub fn redelegate(ctx: Context<Redelegate>, amount: u64) -> Result<()> {
+        instructions::redelegate(ctx, amount)
+    }
+
+    // Release redelegated stake from its source agent's slashes once the exposure ran out (permissionless)
+    pub fn release_exposure(ctx: Context<ReleaseExposure>) -> Result<()> {
+        instructions::release_exposure(ctx)
+    }
+
+    // Withdraw tokens from a matured unbonding ticket
+    pub fn withdraw_unbonded(ctx: Context<WithdrawUnbonded>, ticket_id: u64) -> Result<()> {
+        instructions::withdraw_unbonded(ctx, ticket_id)
+    }
+
+    // Claim the rewards accrued on a stake position
+    pub fn claim_stake_rewards<'info>(ctx: Context<'_, '_, '_, 'info, ClaimStakeRewards<'info>>) -> Result<()> {
+        instructions::claim_rewards(ctx)
+    }
+
+    // Claim commission earned by an agent owner on delegators' rewards
+    pub fn claim_commission<'info>(ctx: Context<'_, '_, '_, 'info, ClaimCommission<'info>>) -> Result<()> {
+        instructions::claim_commission(ctx)
+    }
+
+    // Publish an epoch's merkle root and fund its vault (admin only)
+    pub fn create_distributor(
+        ctx: Context<CreateDistributor>,
+        epoch: u64,
+        root: [u8; 32],
+        total_amount: u64,
+        num_claims: u64,
+        expires_at: i64,
+    ) -> Result<()> {
+        distributor::create_distributor(ctx, epoch, root, total_amount, num_claims, expires_at)
+    }
+
+    // Claim one leaf of a distributor
+    pub fn claim_merkle_reward(
+        ctx: Context<ClaimMerkleReward>,
+        index: u64,
+        amount: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        distributor::claim_merkle_reward(ctx, index, amount, proof)
+    }
+
+    // Return unclaimed funds after the distributor expired (admin only)
+    pub fn clawback_distributor(ctx: Context<ClawbackDistributor>) -> Result<()> {
+        distributor::clawback_distributor(ctx)
+    }
+
+    // Open a vesting escrow for claimed rewards
+    pub fn create_vesting(ctx: Context<CreateVesting>) -> Result<()> {
+        vesting::create_vesting(ctx)
+    }
+
+    // Release the vested part of an escrow
+    pub fn release_vested(ctx: Context<ReleaseVested>) -> Result<()> {
+        vesting::release_vested(ctx)
+    }
+
+    // Leave vesting early, forfeiting part of the unvested balance
+    pub fn exit_vesting_early(ctx: Context<ReleaseVested>) -> Result<()> {
+        vesting::exit_vesting_early(ctx)
+    }
+
+    // Mint the 1/1 ownership NFT of an agent
+    pub fn mint_agent_nft(ctx: Context<MintAgentNft>, uri: String) -> Result<()> {
+        agent_nft::mint_agent_nft(ctx, uri)
+    }
+
+    // Propose slashing an agent's delegated stake (admin or evaluator)
+    pub fn propose_slash(ctx: Context<ProposeSlash>, slash_bps: u64, reason_hash: [u8; 32]) -> Result<()> {
+        slashing::propose_slash(ctx, slash_bps, reason_hash)
+    }
+
+    // Dispute a proposed slash within the dispute window (agent owner only)
+    pub fn appeal_slash(ctx: Context<AppealSlash>) -> Result<()> {
+        slashing::appeal_slash(ctx)
+    }
+
+    // Uphold or cancel an appealed slash (admin only)
+    pub fn resolve_slash_appeal(ctx: Context<ResolveSlashAppeal>, uphold: bool) -> Result<()> {
+        slashing::resolve_slash_appeal(ctx, uphold)
+    }
+
+    // Drop an appealed slash that was not resolved by its deadline
+    pub fn lapse_slash_appeal(ctx: Context<LapseSlashAppeal>) -> Result<()> {
+        slashing::lapse_slash_appeal(ctx)
+    }
+
+    // Finalize an undisputed or upheld slash
+    pub fn finalize_slash(ctx: Context<FinalizeSlash>) -> Result<()> {
+        slashing::finalize_slash(ctx)
+    }
+
+    // Apply a finalized slash to a batch of stake positions
+    pub fn apply_slash<'info>(ctx: Context<'_, '_, '_, 'info, ApplySlash<'info>>) -> Result<()> {
+        slashing::apply_slash(ctx)
+    }
+
+    // Apply a finalized slash to stake redelegated away from the agent
+    pub fn apply_slash_to_redelegation(ctx: Context<ApplySlashToRedelegation>) -> Result<()> {
+        slashing::apply_slash_to_redelegation(ctx)
+    }
+
+    // Apply a finalized slash to tokens unstaked from the agent that are still unbonding
+    pub fn apply_slash_to_ticket(ctx: Context<ApplySlashToTicket>) -> Result<()> {
+        slashing::apply_slash_to_ticket(ctx)
+    }
+
+    // Open a staker-voted governance proposal
+    pub fn create_governance_proposal(
+        ctx: Context<CreateGovernanceProposal>,
+        title: String,
+        description: String,
+        voting_duration: u64,
+        options: Vec<String>,
+    ) -> Result<()> {
+        governance::create_proposal(ctx, title, description, voting_duration, options)
+    }
+
+    // Vote on a governance proposal with the active stake of the voter's positions
+    pub fn cast_vote<'info>(
+        ctx: Context<'_, '_, '_, 'info, CastVote<'info>>,
+        proposal_id: u64,
+        vote_option: u8,
+    ) -> Result<()> {
+        governance::cast_vote(ctx, proposal_id, vote_option)
+    }
+
+    // Close voting on a governance proposal and record its result
+    pub fn finalize_proposal(ctx: Context<FinalizeProposal>, proposal_id: u64) -> Result<()> {
+        governance::finalize_proposal(ctx, proposal_id)
+    }
 }
 
 // Context structs for instruction validation
 #[derive(Accounts)]
 pub struct InitializeRewardPool<'info> {
-    #[account(init, payer = user, space = 8 + 16 + 8, seeds = [b"reward_pool"], bump)]
+    #[account(init, payer = user, space = 8 + 16 + 8 + 8 + 8 + 8 + 8 + 8 + 8, seeds = [b"reward_pool"], bump)]
     pub reward_pool: Account<'info, RewardPool>,
     #[account(mut)]
     pub user: Signer<'info>,
@@ -257,8 +894,10 @@ pub struct InitializeRewardPool<'info> {
 
 #[derive(Accounts)]
 pub struct RegisterAIAgent<'info> {
-    #[account(init, payer = user, space = 8 + 32 + 8 + 8 + 8 + 1 + 1, seeds = [b"ai_agent", user.key().as_ref()], bump)]
+    #[account(init, payer = user, space = 8 + 32 + 8 + 8 + 8 + 1 + 8 + 8 + 1, seeds = [b"ai_agent", user.key().as_ref()], bump)]
     pub ai_agent: Account<'info, AIAgent>,
+    #[account(mut, seeds = [b"reward_pool"], bump = reward_pool.bump)]
+    pub reward_pool: Account<'info, RewardPool>,
     #[account(mut)]
     pub user: Signer<'info>,
     #[account(mut)]
@@ -273,6 +912,8 @@ pub struct RegisterAIAgent<'info> {
 pub struct UnstakeAIAgent<'info> {
     #[account(mut, has_one = owner @ OntoraError::InvalidOwner)]
     pub ai_agent: Account<'info, AIAgent>,
+    #[account(mut, seeds = [b"reward_pool"], bump = reward_pool.bump)]
+    pub reward_pool: Account<'info, RewardPool>,
     #[account(mut)]
     pub user: Signer<'info>,
     #[account(mut)]
@@ -284,24 +925,48 @@ pub struct UnstakeAIAgent<'info> {
 
 #[derive(Accounts)]
 pub struct DistributeRewards<'info> {
-    #[account(mut)]
+    #[account(mut, seeds = [b"reward_pool"], bump = reward_pool.bump)]
     pub reward_pool: Account<'info, RewardPool>,
     #[account(mut)]
     pub ai_agent: Account<'info, AIAgent>,
     pub user: Signer<'info>,
 }
 
+#[derive(Accounts)]
+pub struct CrankRewards<'info> {
+    #[account(mut, seeds = [b"reward_pool"], bump = reward_pool.bump)]
+    pub reward_pool: Account<'info, RewardPool>,
+    #[account(mut, constraint = reward_vault.owner == reward_pool.key() @ FabeonError::InvalidRewardVault)]
+    pub reward_vault: Account<'info, TokenAccount>,
+    pub keeper: Signer<'info>,
+    #[account(mut, constraint = keeper_token_account.mint == reward_vault.mint @ FabeonError::InvalidRewardVault)]
+    pub keeper_token_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct TopUpRewardPool<'info> {
+    #[account(mut, seeds = [b"reward_pool"], bump = reward_pool.bump)]
+    pub reward_pool: Account<'info, RewardPool>,
+    #[account(mut, constraint = reward_vault.owner == reward_pool.key() @ FabeonError::InvalidRewardVault)]
+    pub reward_vault: Account<'info, TokenAccount>,
+    pub funder: Signer<'info>,
+    #[account(mut)]
+    pub funder_token_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
 #[derive(Accounts)]
 pub struct ClaimRewards<'info> {
     #[account(mut)]
     pub ai_agent: Account<'info, AIAgent>,
-    #[account(mut)]
+    #[account(mut, seeds = [b"reward_pool"], bump = reward_pool.bump)]
     pub reward_pool: Account<'info, RewardPool>,
     #[account(mut)]
     pub user: Signer<'info>,
     #[account(mut)]
     pub user_token_account: Account<'info, TokenAccount>,
-    #[account(mut)]
+    #[account(mut, constraint = reward_vault.owner == reward_pool.key() @ FabeonError::InvalidRewardVault)]
     pub reward_vault: Account<'info, TokenAccount>,
     pub token_program: Program<'info, Token>,
 }
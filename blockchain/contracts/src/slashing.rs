@@ -0,0 +1,757 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+use crate::state::{AiAgent, EmissionSchedule, PlatformConfig, StakePosition, UnbondingTicket, UserStake};
+use crate::instructions::transfer_from_vault;
+use crate::events::{SlashAppealed, SlashApplied, SlashCancelled, SlashFinalized, SlashProposed};
+
+// Maximum number of positions `apply_slash` processes in one transaction
+pub const MAX_SLASH_BATCH: usize = 10;
+
+// Progress of a slash proposal
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SlashStatus {
+    // Open for appeal until the dispute deadline
+    #[default]
+    Pending,
+    // Appealed by the agent owner; waiting for governance to decide until the resolution deadline
+    Appealed,
+    // Final; being applied to the agent's positions
+    Applying,
+    // Applied to every position
+    Completed,
+    // Dropped on appeal, or lapsed because the appeal was not decided in time
+    Cancelled,
+}
+
+// Proposal to slash a share of an agent's delegated stake
+#[account]
+#[derive(Default)]
+pub struct SlashProposal {
+    // Agent being slashed
+    pub agent: Pubkey,
+    // Sequence number of the proposal on the agent
+    pub slash_id: u32,
+    // Admin or evaluator that raised the proposal
+    pub proposer: Pubkey,
+    // Share of each position taken (in basis points)
+    pub slash_bps: u64,
+    // Hash of the off-chain evidence
+    pub reason_hash: [u8; 32],
+    // Current status
+    pub status: SlashStatus,
+    // Timestamp the proposal was raised
+    pub proposed_at: i64,
+    // The owner can appeal up to this time; afterwards anyone can finalize
+    pub dispute_deadline: i64,
+    // Once appealed, governance must decide by this time or the slash lapses
+    pub resolution_deadline: i64,
+    // Positions and unbonding tickets still to be slashed once applying
+    pub positions_remaining: u64,
+    // Total stake moved to the treasury so far
+    pub slashed_amount: u64,
+    // Bump seed for PDA derivation
+    pub bump: u8,
+}
+
+impl SlashProposal {
+    #[allow(clippy::too_many_arguments)]
+    pub fn init(
+        &mut self,
+        agent: Pubkey,
+        slash_id: u32,
+        proposer: Pubkey,
+        slash_bps: u64,
+        reason_hash: [u8; 32],
+        now: i64,
+        dispute_window: i64,
+        bump: u8,
+    ) -> Result<()> {
+        self.agent = agent;
+        self.slash_id = slash_id;
+        self.proposer = proposer;
+        self.slash_bps = slash_bps;
+        self.reason_hash = reason_hash;
+        self.status = SlashStatus::Pending;
+        self.proposed_at = now;
+        self.dispute_deadline = now.checked_add(dispute_window).ok_or(SlashingError::MathOverflow)?;
+        self.resolution_deadline = 0;
+        self.positions_remaining = 0;
+        self.slashed_amount = 0;
+        self.bump = bump;
+        Ok(())
+    }
+
+    // Put the proposal under appeal; only possible before the dispute deadline.
+    // Governance then has `resolution_window` seconds to decide.
+    pub fn appeal(&mut self, now: i64, resolution_window: i64) -> Result<()> {
+        require!(self.status == SlashStatus::Pending, SlashingError::InvalidSlashStatus);
+        require!(now <= self.dispute_deadline, SlashingError::DisputeWindowClosed);
+        self.status = SlashStatus::Appealed;
+        self.resolution_deadline = now.checked_add(resolution_window).ok_or(SlashingError::MathOverflow)?;
+        Ok(())
+    }
+
+    // Whether an appeal can still be decided at `now`
+    pub fn can_resolve(&self, now: i64) -> bool {
+        self.status == SlashStatus::Appealed && now <= self.resolution_deadline
+    }
+
+    // Make the slash final: every open position on the agent, every position still exposed
+    // to it after a redelegation and every unbonding ticket from it now has to be slashed.
+    // Completes at once if there are none.
+    pub fn finalize(&mut self, agent: &mut AiAgent) -> Result<()> {
+        agent.slash_count = agent.slash_count.checked_add(1).ok_or(SlashingError::MathOverflow)?;
+        self.status = SlashStatus::Applying;
+        self.positions_remaining = agent.position_count
+            .checked_add(agent.outgoing_exposures)
+            .and_then(|v| v.checked_add(agent.unbonding_tickets))
+            .ok_or(SlashingError::MathOverflow)?;
+        self.complete_if_done(agent);
+        Ok(())
+    }
+
+    // Drop the slash and release the agent's stake
+    pub fn cancel(&mut self, agent: &mut AiAgent) {
+        self.status = SlashStatus::Cancelled;
+        agent.slash_pending = false;
+    }
+
+    // Record a processed batch of positions
+    pub fn record_batch(&mut self, agent: &mut AiAgent, positions: u64, amount: u64) -> Result<()> {
+        self.positions_remaining = self.positions_remaining.checked_sub(positions).ok_or(SlashingError::InvalidPosition)?;
+        self.slashed_amount = self.slashed_amount.checked_add(amount).ok_or(SlashingError::MathOverflow)?;
+        self.complete_if_done(agent);
+        Ok(())
+    }
+
+    fn complete_if_done(&mut self, agent: &mut AiAgent) {
+        if self.positions_remaining == 0 {
+            self.status = SlashStatus::Completed;
+            agent.slash_pending = false;
+        }
+    }
+
+    pub const SPACE: usize = 8 + // discriminator
+        32 + // agent (Pubkey)
+        4 + // slash_id (u32)
+        32 + // proposer (Pubkey)
+        8 + // slash_bps (u64)
+        32 + // reason_hash ([u8; 32])
+        1 + // status (SlashStatus)
+        8 + // proposed_at (i64)
+        8 + // dispute_deadline (i64)
+        8 + // resolution_deadline (i64)
+        8 + // positions_remaining (u64)
+        8 + // slashed_amount (u64)
+        1; // bump (u8)
+}
+
+// Propose slashing an agent's delegated stake (admin or evaluator)
+#[derive(Accounts)]
+pub struct ProposeSlash<'info> {
+    #[account(
+        seeds = [b"platform-config"],
+        bump = platform_config.bump,
+        constraint = proposer.key() == platform_config.admin || proposer.key() == platform_config.evaluator @ SlashingError::Unauthorized
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+    #[account(
+        mut,
+        seeds = [b"ai-agent", ai_agent.owner.as_ref(), &ai_agent.agent_id.to_le_bytes()],
+        bump = ai_agent.bump,
+        constraint = !ai_agent.slash_pending @ SlashingError::SlashAlreadyPending
+    )]
+    pub ai_agent: Account<'info, AiAgent>,
+    #[account(
+        init,
+        payer = proposer,
+        space = SlashProposal::SPACE,
+        seeds = [b"slash", ai_agent.key().as_ref(), &ai_agent.slash_proposal_count.to_le_bytes()],
+        bump
+    )]
+    pub slash_proposal: Account<'info, SlashProposal>,
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn propose_slash(ctx: Context<ProposeSlash>, slash_bps: u64, reason_hash: [u8; 32]) -> Result<()> {
+    let platform_config = &ctx.accounts.platform_config;
+    let ai_agent = &mut ctx.accounts.ai_agent;
+    let clock = Clock::get()?;
+
+    require!(platform_config.slash_treasury != Pubkey::default(), SlashingError::SlashingDisabled);
+    require!(slash_bps > 0 && slash_bps <= platform_config.max_slash_bps, SlashingError::InvalidSlashBps);
+    require!(ai_agent.has_slashable_stake(), SlashingError::NothingToSlash);
+
+    let slash_id = ai_agent.slash_proposal_count;
+    ctx.accounts.slash_proposal.init(
+        ai_agent.key(),
+        slash_id,
+        ctx.accounts.proposer.key(),
+        slash_bps,
+        reason_hash,
+        clock.unix_timestamp,
+        platform_config.slash_dispute_window,
+        ctx.bumps.slash_proposal,
+    )?;
+    ai_agent.slash_proposal_count = slash_id.checked_add(1).ok_or(SlashingError::MathOverflow)?;
+    ai_agent.slash_pending = true;
+
+    emit!(SlashProposed {
+        agent: ai_agent.key(),
+        slash_id,
+        proposer: ctx.accounts.proposer.key(),
+        slash_bps,
+        reason_hash,
+        dispute_deadline: ctx.accounts.slash_proposal.dispute_deadline,
+    });
+
+    msg!("Slash {} of {} bps proposed against agent {}", slash_id, slash_bps, ai_agent.agent_id);
+    Ok(())
+}
+
+// Appeal a pending slash within the dispute window (agent owner)
+#[derive(Accounts)]
+pub struct AppealSlash<'info> {
+    #[account(
+        seeds = [b"platform-config"],
+        bump = platform_config.bump
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+    #[account(
+        seeds = [b"ai-agent", ai_agent.owner.as_ref(), &ai_agent.agent_id.to_le_bytes()],
+        bump = ai_agent.bump,
+        constraint = ai_agent.is_controlled_by(&owner.key(), owner_nft_account.as_deref()) @ SlashingError::Unauthorized
+    )]
+    pub ai_agent: Account<'info, AiAgent>,
+    #[account(
+        mut,
+        seeds = [b"slash", ai_agent.key().as_ref(), &slash_proposal.slash_id.to_le_bytes()],
+        bump = slash_proposal.bump
+    )]
+    pub slash_proposal: Account<'info, SlashProposal>,
+    pub owner: Signer<'info>,
+    // Required once the agent is tokenized: the signer's token account holding the ownership NFT
+    pub owner_nft_account: Option<Account<'info, TokenAccount>>,
+}
+
+pub fn appeal_slash(ctx: Context<AppealSlash>) -> Result<()> {
+    let slash_proposal = &mut ctx.accounts.slash_proposal;
+    let clock = Clock::get()?;
+
+    slash_proposal.appeal(clock.unix_timestamp, ctx.accounts.platform_config.slash_resolution_window)?;
+
+    emit!(SlashAppealed {
+        agent: slash_proposal.agent,
+        slash_id: slash_proposal.slash_id,
+        timestamp: clock.unix_timestamp,
+        resolution_deadline: slash_proposal.resolution_deadline,
+    });
+
+    msg!("Slash {} against agent {} appealed", slash_proposal.slash_id, ctx.accounts.ai_agent.agent_id);
+    Ok(())
+}
+
+// Decide an appealed slash before its resolution deadline: uphold it or drop it (admin only)
+#[derive(Accounts)]
+pub struct ResolveSlashAppeal<'info> {
+    #[account(
+        seeds = [b"platform-config"],
+        bump = platform_config.bump,
+        has_one = admin @ SlashingError::Unauthorized
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+    #[account(
+        mut,
+        seeds = [b"ai-agent", ai_agent.owner.as_ref(), &ai_agent.agent_id.to_le_bytes()],
+        bump = ai_agent.bump
+    )]
+    pub ai_agent: Account<'info, AiAgent>,
+    #[account(
+        mut,
+        seeds = [b"slash", ai_agent.key().as_ref(), &slash_proposal.slash_id.to_le_bytes()],
+        bump = slash_proposal.bump,
+        constraint = slash_proposal.status == SlashStatus::Appealed @ SlashingError::InvalidSlashStatus
+    )]
+    pub slash_proposal: Account<'info, SlashProposal>,
+    pub admin: Signer<'info>,
+}
+
+pub fn resolve_slash_appeal(ctx: Context<ResolveSlashAppeal>, uphold: bool) -> Result<()> {
+    let ai_agent = &mut ctx.accounts.ai_agent;
+    let slash_proposal = &mut ctx.accounts.slash_proposal;
+    let clock = Clock::get()?;
+
+    require!(slash_proposal.can_resolve(clock.unix_timestamp), SlashingError::ResolutionWindowClosed);
+
+    if uphold {
+        slash_proposal.finalize(ai_agent)?;
+        emit!(SlashFinalized {
+            agent: ai_agent.key(),
+            slash_id: slash_proposal.slash_id,
+            positions: slash_proposal.positions_remaining,
+            timestamp: clock.unix_timestamp,
+        });
+    } else {
+        slash_proposal.cancel(ai_agent);
+        emit!(SlashCancelled {
+            agent: ai_agent.key(),
+            slash_id: slash_proposal.slash_id,
+            timestamp: clock.unix_timestamp,
+        });
+    }
+
+    msg!(
+        "Appeal of slash {} against agent {} resolved: {}",
+        slash_proposal.slash_id,
+        ai_agent.agent_id,
+        if uphold { "upheld" } else { "cancelled" }
+    );
+    Ok(())
+}
+
+// Drop an appealed slash governance did not decide by the resolution deadline (permissionless)
+#[derive(Accounts)]
+pub struct LapseSlashAppeal<'info> {
+    #[account(
+        mut,
+        seeds = [b"ai-agent", ai_agent.owner.as_ref(), &ai_agent.agent_id.to_le_bytes()],
+        bump = ai_agent.bump
+    )]
+    pub ai_agent: Account<'info, AiAgent>,
+    #[account(
+        mut,
+        seeds = [b"slash", ai_agent.key().as_ref(), &slash_proposal.slash_id.to_le_bytes()],
+        bump = slash_proposal.bump,
+        constraint = slash_proposal.status == SlashStatus::Appealed @ SlashingError::InvalidSlashStatus
+    )]
+    pub slash_proposal: Account<'info, SlashProposal>,
+}
+
+pub fn lapse_slash_appeal(ctx: Context<LapseSlashAppeal>) -> Result<()> {
+    let ai_agent = &mut ctx.accounts.ai_agent;
+    let slash_proposal = &mut ctx.accounts.slash_proposal;
+    let clock = Clock::get()?;
+
+    require!(!slash_proposal.can_resolve(clock.unix_timestamp), SlashingError::ResolutionWindowOpen);
+
+    slash_proposal.cancel(ai_agent);
+
+    emit!(SlashCancelled {
+        agent: ai_agent.key(),
+        slash_id: slash_proposal.slash_id,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Appeal of slash {} against agent {} was not resolved in time; slash lapsed", slash_proposal.slash_id, ai_agent.agent_id);
+    Ok(())
+}
+
+// Finalize an unappealed slash once the dispute window has closed (permissionless)
+#[derive(Accounts)]
+pub struct FinalizeSlash<'info> {
+    #[account(
+        mut,
+        seeds = [b"ai-agent", ai_agent.owner.as_ref(), &ai_agent.agent_id.to_le_bytes()],
+        bump = ai_agent.bump
+    )]
+    pub ai_agent: Account<'info, AiAgent>,
+    #[account(
+        mut,
+        seeds = [b"slash", ai_agent.key().as_ref(), &slash_proposal.slash_id.to_le_bytes()],
+        bump = slash_proposal.bump,
+        constraint = slash_proposal.status == SlashStatus::Pending @ SlashingError::InvalidSlashStatus
+    )]
+    pub slash_proposal: Account<'info, SlashProposal>,
+}
+
+pub fn finalize_slash(ctx: Context<FinalizeSlash>) -> Result<()> {
+    let ai_agent = &mut ctx.accounts.ai_agent;
+    let slash_proposal = &mut ctx.accounts.slash_proposal;
+    let clock = Clock::get()?;
+
+    require!(clock.unix_timestamp > slash_proposal.dispute_deadline, SlashingError::DisputeWindowOpen);
+
+    slash_proposal.finalize(ai_agent)?;
+
+    emit!(SlashFinalized {
+        agent: ai_agent.key(),
+        slash_id: slash_proposal.slash_id,
+        positions: slash_proposal.positions_remaining,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Slash {} against agent {} finalized", slash_proposal.slash_id, ai_agent.agent_id);
+    Ok(())
+}
+
+// Apply a finalized slash to a batch of the agent's positions and move the stake taken
+// to the treasury (permissionless)
+#[derive(Accounts)]
+pub struct ApplySlash<'info> {
+    #[account(
+        mut,
+        seeds = [b"platform-config"],
+        bump = platform_config.bump
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+    #[account(
+        mut,
+        seeds = [b"emission-schedule"],
+        bump = emission_schedule.bump
+    )]
+    pub emission_schedule: Account<'info, EmissionSchedule>,
+    #[account(
+        mut,
+        seeds = [b"ai-agent", ai_agent.owner.as_ref(), &ai_agent.agent_id.to_le_bytes()],
+        bump = ai_agent.bump
+    )]
+    pub ai_agent: Account<'info, AiAgent>,
+    #[account(
+        mut,
+        seeds = [b"slash", ai_agent.key().as_ref(), &slash_proposal.slash_id.to_le_bytes()],
+        bump = slash_proposal.bump,
+        constraint = slash_proposal.status == SlashStatus::Applying @ SlashingError::InvalidSlashStatus
+    )]
+    pub slash_proposal: Account<'info, SlashProposal>,
+    #[account(
+        mut,
+        seeds = [b"stake-vault"],
+        bump = platform_config.stake_vault_bump,
+        token::mint = platform_config.stake_mint,
+        token::authority = platform_config
+    )]
+    pub stake_vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        address = platform_config.slash_treasury @ SlashingError::InvalidTreasury
+    )]
+    pub slash_treasury: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+// Expects (stake_position, user_stake) pairs in remaining_accounts, one per position on the agent
+pub fn apply_slash<'info>(ctx: Context<'_, '_, '_, 'info, ApplySlash<'info>>) -> Result<()> {
+    let platform_config = &mut ctx.accounts.platform_config;
+    let ai_agent = &mut ctx.accounts.ai_agent;
+    let slash_proposal = &mut ctx.accounts.slash_proposal;
+    let clock = Clock::get()?;
+
+    require!(ctx.remaining_accounts.len() % 2 == 0, SlashingError::InvalidPosition);
+    let positions = ctx.remaining_accounts.len() / 2;
+    require!(positions > 0 && positions <= MAX_SLASH_BATCH, SlashingError::InvalidBatchSize);
+
+    platform_config.accrue_rewards_fully(&mut ctx.accounts.emission_schedule, clock.unix_timestamp)?;
+    ai_agent.settle_rewards(platform_config)?;
+
+    let mut total_cut: u64 = 0;
+    for pair in ctx.remaining_accounts.chunks(2) {
+        let mut stake_position = Account::<StakePosition>::try_from(&pair[0])?;
+        let mut user_stake = Account::<UserStake>::try_from(&pair[1])?;
+        require_keys_eq!(stake_position.agent, ai_agent.key(), SlashingError::InvalidPosition);
+        require_keys_eq!(user_stake.user, stake_position.user, SlashingError::InvalidPosition);
+        // Each open position is slashed exactly once
+        require!(
+            stake_position.amount > 0 && stake_position.slash_count < ai_agent.slash_count,
+            SlashingError::InvalidPosition
+        );
+
+        // Settle rewards earned on the unslashed amount before it changes
+        ai_agent.settle_position(stake_position)?;
+
+        let activating = stake_position.activating_amount;
+        let cut = stake_position.apply_slash(slash_proposal.slash_bps, ai_agent.slash_count)?;
+        platform_config.sync_activating(activating, stake_position.activating_amount)?;
+        user_stake.staked_amount = user_stake.staked_amount.checked_sub(cut).ok_or(SlashingError::MathOverflow)?;
+        ai_agent.staked_amount = ai_agent.staked_amount.checked_sub(cut).ok_or(SlashingError::MathOverflow)?;
+        platform_config.total_staked = platform_config.total_staked.checked_sub(cut).ok_or(SlashingError::MathOverflow)?;
+        let (old_weight, new_weight) = stake_position.refresh_weight()?;
+        platform_config.apply_position_weight_change(ai_agent, old_weight, new_weight)?;
+        stake_position.reset_reward_debt(ai_agent.acc_reward_per_share)?;
+        stake_position.reset_slot_reward_debts(&ai_agent.slot_acc_per_share)?;
+
+        if stake_position.amount == 0 {
+            user_stake.position_count = user_stake.position_count.saturating_sub(1);
+            ai_agent.position_count = ai_agent.position_count.saturating_sub(1);
+        }
+        total_cut = total_cut.checked_add(cut).ok_or(SlashingError::MathOverflow)?;
+
+        stake_position.exit(ctx.program_id)?;
+        user_stake.exit(ctx.program_id)?;
+    }
+
+    slash_proposal.record_batch(ai_agent, positions as u64, total_cut)?;
+
+    if total_cut > 0 {
+        transfer_from_vault(
+            platform_config,
+            ctx.accounts.stake_vault.to_account_info(),
+            ctx.accounts.slash_treasury.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            total_cut,
+        )?;
+    }
+
+    emit!(SlashApplied {
+        agent: ai_agent.key(),
+        slash_id: slash_proposal.slash_id,
+        positions: positions as u64,
+        amount: total_cut,
+        positions_remaining: slash_proposal.positions_remaining,
+    });
+
+    msg!(
+        "Slash {} applied to {} positions of agent {}: {} moved to treasury, {} positions remaining",
+        slash_proposal.slash_id,
+        positions,
+        ai_agent.agent_id,
+        total_cut,
+        slash_proposal.positions_remaining
+    );
+    Ok(())
+}
+
+// Apply a finalized slash to stake redelegated away from the slashed agent while it is still
+// exposed (permissionless). Counts towards the proposal's positions like `apply_slash`.
+#[derive(Accounts)]
+pub struct ApplySlashToRedelegation<'info> {
+    #[account(
+        mut,
+        seeds = [b"platform-config"],
+        bump = platform_config.bump
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+    #[account(
+        mut,
+        seeds = [b"emission-schedule"],
+        bump = emission_schedule.bump
+    )]
+    pub emission_schedule: Account<'info, EmissionSchedule>,
+    #[account(
+        mut,
+        seeds = [b"ai-agent", ai_agent.owner.as_ref(), &ai_agent.agent_id.to_le_bytes()],
+        bump = ai_agent.bump
+    )]
+    pub ai_agent: Account<'info, AiAgent>,
+    #[account(
+        mut,
+        seeds = [b"slash", ai_agent.key().as_ref(), &slash_proposal.slash_id.to_le_bytes()],
+        bump = slash_proposal.bump,
+        constraint = slash_proposal.status == SlashStatus::Applying @ SlashingError::InvalidSlashStatus
+    )]
+    pub slash_proposal: Account<'info, SlashProposal>,
+    // Agent the stake was redelegated to
+    #[account(
+        mut,
+        seeds = [b"ai-agent", target_agent.owner.as_ref(), &target_agent.agent_id.to_le_bytes()],
+        bump = target_agent.bump
+    )]
+    pub target_agent: Account<'info, AiAgent>,
+    #[account(
+        mut,
+        seeds = [b"stake-position", stake_position.user.as_ref(), target_agent.key().as_ref()],
+        bump = stake_position.bump,
+        constraint = stake_position.exposure_agent == ai_agent.key() @ SlashingError::InvalidPosition,
+        constraint = stake_position.exposure_slash_count < ai_agent.slash_count @ SlashingError::InvalidPosition
+    )]
+    pub stake_position: Account<'info, StakePosition>,
+    #[account(
+        mut,
+        seeds = [b"user-stake", stake_position.user.as_ref()],
+        bump = user_stake.bump
+    )]
+    pub user_stake: Account<'info, UserStake>,
+    #[account(
+        mut,
+        seeds = [b"stake-vault"],
+        bump = platform_config.stake_vault_bump,
+        token::mint = platform_config.stake_mint,
+        token::authority = platform_config
+    )]
+    pub stake_vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        address = platform_config.slash_treasury @ SlashingError::InvalidTreasury
+    )]
+    pub slash_treasury: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn apply_slash_to_redelegation(ctx: Context<ApplySlashToRedelegation>) -> Result<()> {
+    let platform_config = &mut ctx.accounts.platform_config;
+    let ai_agent = &mut ctx.accounts.ai_agent;
+    let slash_proposal = &mut ctx.accounts.slash_proposal;
+    let target_agent = &mut ctx.accounts.target_agent;
+    let stake_position = &mut ctx.accounts.stake_position;
+    let user_stake = &mut ctx.accounts.user_stake;
+    let clock = Clock::get()?;
+
+    // Settle rewards earned on the unslashed amount before it changes
+    platform_config.accrue_rewards_fully(&mut ctx.accounts.emission_schedule, clock.unix_timestamp)?;
+    target_agent.settle_rewards(platform_config)?;
+    target_agent.settle_position(stake_position)?;
+
+    let activating = stake_position.activating_amount;
+    let cut = stake_position.apply_exposure_slash(slash_proposal.slash_bps, ai_agent.slash_count, slash_proposal.proposed_at)?;
+    platform_config.sync_activating(activating, stake_position.activating_amount)?;
+    user_stake.staked_amount = user_stake.staked_amount.checked_sub(cut).ok_or(SlashingError::MathOverflow)?;
+    target_agent.staked_amount = target_agent.staked_amount.checked_sub(cut).ok_or(SlashingError::MathOverflow)?;
+    platform_config.total_staked = platform_config.total_staked.checked_sub(cut).ok_or(SlashingError::MathOverflow)?;
+    let (old_weight, new_weight) = stake_position.refresh_weight()?;
+    platform_config.apply_position_weight_change(target_agent, old_weight, new_weight)?;
+    stake_position.reset_reward_debt(target_agent.acc_reward_per_share)?;
+    stake_position.reset_slot_reward_debts(&target_agent.slot_acc_per_share)?;
+
+    if stake_position.amount == 0 {
+        user_stake.position_count = user_stake.position_count.saturating_sub(1);
+        target_agent.position_count = target_agent.position_count.saturating_sub(1);
+    }
+
+    slash_proposal.record_batch(ai_agent, 1, cut)?;
+
+    if cut > 0 {
+        transfer_from_vault(
+            platform_config,
+            ctx.accounts.stake_vault.to_account_info(),
+            ctx.accounts.slash_treasury.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            cut,
+        )?;
+    }
+
+    emit!(SlashApplied {
+        agent: ai_agent.key(),
+        slash_id: slash_proposal.slash_id,
+        positions: 1,
+        amount: cut,
+        positions_remaining: slash_proposal.positions_remaining,
+    });
+
+    msg!(
+        "Slash {} of agent {} applied to stake redelegated to agent {}: {} moved to treasury",
+        slash_proposal.slash_id,
+        ai_agent.agent_id,
+        target_agent.agent_id,
+        cut
+    );
+    Ok(())
+}
+
+// Apply a finalized slash to tokens unstaked from the agent that are still unbonding
+// (permissionless). Counts towards the proposal's positions like `apply_slash`.
+#[derive(Accounts)]
+pub struct ApplySlashToTicket<'info> {
+    #[account(
+        seeds = [b"platform-config"],
+        bump = platform_config.bump
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+    #[account(
+        mut,
+        seeds = [b"ai-agent", ai_agent.owner.as_ref(), &ai_agent.agent_id.to_le_bytes()],
+        bump = ai_agent.bump
+    )]
+    pub ai_agent: Account<'info, AiAgent>,
+    #[account(
+        mut,
+        seeds = [b"slash", ai_agent.key().as_ref(), &slash_proposal.slash_id.to_le_bytes()],
+        bump = slash_proposal.bump,
+        constraint = slash_proposal.status == SlashStatus::Applying @ SlashingError::InvalidSlashStatus
+    )]
+    pub slash_proposal: Account<'info, SlashProposal>,
+    #[account(
+        mut,
+        seeds = [b"unbonding-ticket", unbonding_ticket.user.as_ref(), &unbonding_ticket.ticket_id.to_le_bytes()],
+        bump = unbonding_ticket.bump,
+        constraint = unbonding_ticket.agent == ai_agent.key() @ SlashingError::InvalidPosition,
+        constraint = unbonding_ticket.slash_count < ai_agent.slash_count @ SlashingError::InvalidPosition
+    )]
+    pub unbonding_ticket: Account<'info, UnbondingTicket>,
+    #[account(
+        mut,
+        seeds = [b"stake-vault"],
+        bump = platform_config.stake_vault_bump,
+        token::mint = platform_config.stake_mint,
+        token::authority = platform_config
+    )]
+    pub stake_vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        address = platform_config.slash_treasury @ SlashingError::InvalidTreasury
+    )]
+    pub slash_treasury: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn apply_slash_to_ticket(ctx: Context<ApplySlashToTicket>) -> Result<()> {
+    let ai_agent = &mut ctx.accounts.ai_agent;
+    let slash_proposal = &mut ctx.accounts.slash_proposal;
+    let unbonding_ticket = &mut ctx.accounts.unbonding_ticket;
+
+    // Unbonding tokens no longer earn rewards, so there is nothing to settle first
+    let cut = unbonding_ticket.apply_slash(slash_proposal.slash_bps, ai_agent.slash_count, slash_proposal.proposed_at)?;
+    slash_proposal.record_batch(ai_agent, 1, cut)?;
+
+    if cut > 0 {
+        transfer_from_vault(
+            &ctx.accounts.platform_config,
+            ctx.accounts.stake_vault.to_account_info(),
+            ctx.accounts.slash_treasury.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            cut,
+        )?;
+    }
+
+    emit!(SlashApplied {
+        agent: ai_agent.key(),
+        slash_id: slash_proposal.slash_id,
+        positions: 1,
+        amount: cut,
+        positions_remaining: slash_proposal.positions_remaining,
+    });
+
+    msg!(
+        "Slash {} of agent {} applied to unbonding ticket {} of {}: {} moved to treasury",
+        slash_proposal.slash_id,
+        ai_agent.agent_id,
+        unbonding_ticket.ticket_id,
+        unbonding_ticket.user,
+        cut
+    );
+    Ok(())
+}
+
+#[error_code]
+pub enum SlashingError {
+    #[msg("Unauthorized access.")]
+    Unauthorized,
+    #[msg("Slashing is disabled until a treasury is configured.")]
+    SlashingDisabled,
+    #[msg("Slash must be above zero and within the platform cap.")]
+    InvalidSlashBps,
+    #[msg("Agent has no delegated, redelegated or unbonding stake to slash.")]
+    NothingToSlash,
+    #[msg("A slash against this agent is already pending.")]
+    SlashAlreadyPending,
+    #[msg("Slash proposal is not in the required status.")]
+    InvalidSlashStatus,
+    #[msg("The dispute window has closed.")]
+    DisputeWindowClosed,
+    #[msg("The dispute window is still open.")]
+    DisputeWindowOpen,
+    #[msg("The appeal was not resolved in time and has lapsed.")]
+    ResolutionWindowClosed,
+    #[msg("The appeal can still be resolved.")]
+    ResolutionWindowOpen,
+    #[msg("Stake position or unbonding ticket is not an unslashed open position of this agent.")]
+    InvalidPosition,
+    #[msg("Invalid number of positions in the batch.")]
+    InvalidBatchSize,
+    #[msg("Vault is not owned by the platform.")]
+    InvalidVault,
+    #[msg("Token account is not the slash treasury.")]
+    InvalidTreasury,
+    #[msg("Math overflow.")]
+    MathOverflow,
+}
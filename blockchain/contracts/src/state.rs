@@ -1,9 +1,60 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::keccak;
+use anchor_spl::token::TokenAccount;
 
 // Constants for maximum sizes to prevent excessive memory allocation
 pub const MAX_NAME_LENGTH: usize = 32;
 pub const MAX_DESCRIPTION_LENGTH: usize = 256;
-pub const MAX_AGENTS_PER_USER: usize = 10;
+// Number of prior metadata hashes kept on an agent
+pub const MAX_METADATA_HISTORY: usize = 8;
+// Limits of the typed agent metadata
+pub const MAX_CAPABILITIES: usize = 16;
+pub const MAX_TAGS: usize = 16;
+pub const MAX_LABEL_LENGTH: usize = 32;
+pub const MAX_MODEL_FAMILY_LENGTH: usize = 32;
+// Limits of governance proposals
+pub const MAX_PROPOSAL_TITLE_LENGTH: usize = 100;
+pub const MAX_PROPOSAL_DESCRIPTION_LENGTH: usize = 1000;
+pub const MAX_VOTE_OPTIONS: usize = 10;
+pub const MAX_VOTE_OPTION_LENGTH: usize = 32;
+// Fixed-point scale of the reward-per-token accumulator
+pub const REWARD_PRECISION: u128 = 1_000_000_000_000;
+// Maximum number of segments in a piecewise emission curve
+pub const MAX_EMISSION_SEGMENTS: usize = 16;
+// Maximum number of epochs walked in a single accrual (bounds compute usage)
+pub const MAX_ACCRUAL_EPOCHS: u64 = 64;
+// Number of additional (partner) reward mints paid alongside the primary reward
+pub const MAX_REWARD_SLOTS: usize = 4;
+// Multiplier of unlocked stake (1.0x in basis points)
+pub const BASE_MULTIPLIER_BPS: u64 = 10_000;
+// Longest lock a position can choose (1 year)
+pub const MAX_LOCK_DURATION: i64 = 31_536_000;
+// Lock tiers as (minimum lock duration in seconds, multiplier in basis points),
+// mirroring the lockup bonus multipliers in configs/governance_config.json
+pub const LOCK_TIERS: [(i64, u64); 4] = [
+    (604_800, 10_000),    // 7 days: 1.0x
+    (2_592_000, 15_000),  // 30 days: 1.5x
+    (7_776_000, 20_000),  // 90 days: 2.0x
+    (31_536_000, 30_000), // 365 days: 3.0x
+];
+
+// Multiplier for a lock duration; zero means unlocked
+pub fn lock_multiplier_bps(lock_duration: i64) -> Result<u64> {
+    if lock_duration == 0 {
+        return Ok(BASE_MULTIPLIER_BPS);
+    }
+    require!(
+        lock_duration >= LOCK_TIERS[0].0 && lock_duration <= MAX_LOCK_DURATION,
+        ErrorCode::InvalidLockDuration
+    );
+    let multiplier = LOCK_TIERS
+        .iter()
+        .rev()
+        .find(|(min_duration, _)| lock_duration >= *min_duration)
+        .map(|(_, multiplier)| *multiplier)
+        .unwrap_or(BASE_MULTIPLIER_BPS);
+    Ok(multiplier)
+}
 
 // Global configuration account for the firoxy AI platform
 #[account]
@@ -11,40 +62,729 @@ pub const MAX_AGENTS_PER_USER: usize = 10;
 pub struct PlatformConfig {
     // Platform administrator (can update settings)
     pub admin: Pubkey,
-    // Reward rate per epoch (in basis points, e.g., 100 = 1%)
-    pub reward_rate_bps: u64,
     // Minimum stake required to participate (in lamports or token units)
     pub min_stake_amount: u64,
     // Epoch duration in seconds (e.g., 86400 for 1 day)
     pub epoch_duration: i64,
-    // Timestamp of the last reward distribution
+    // Start of epoch 0; set to the emission schedule's start so stake and emission epochs line up
+    pub epoch_origin: i64,
+    // Delay in seconds between unstaking and being able to withdraw the tokens
+    pub unbonding_period: i64,
+    // Timestamp up to which rewards have been accrued
     pub last_reward_timestamp: i64,
     // Total staked amount across the platform
     pub total_staked: u64,
+    // Total stake scaled by lock multipliers; rewards are shared pro rata to this
+    pub total_weighted_stake: u64,
+    // Share of emissions distributed by weighted stake (in basis points)
+    pub stake_weight_bps: u64,
+    // Share of emissions distributed by agent performance score (in basis points)
+    pub performance_weight_bps: u64,
+    // Sum of the performance scores of agents that currently have stake
+    pub total_performance_score: u64,
+    // Stake-weighted rewards accrued per weighted staked token, scaled by REWARD_PRECISION
+    pub acc_reward_per_share: u128,
+    // Performance-weighted rewards accrued per score point, scaled by REWARD_PRECISION
+    pub acc_reward_per_score: u128,
+    // Role allowed to update agent performance scores
+    pub evaluator: Pubkey,
+    // Mint of the token rewards are paid in
+    pub reward_mint: Pubkey,
+    // Bump seed of the reward vault PDA (holds reward liquidity, separate from staked principal)
+    pub reward_vault_bump: u8,
+    // Mint of the token staked on agents
+    pub stake_mint: Pubkey,
+    // Bump seed of the stake vault PDA (holds staked principal, separate from reward liquidity)
+    pub stake_vault_bump: u8,
+    // Additional reward mints, each with its own vault, rate and accumulator
+    pub reward_slots: [RewardSlot; MAX_REWARD_SLOTS],
+    // Seconds after a claim before vested rewards start releasing
+    pub vesting_cliff: i64,
+    // Seconds over which claimed rewards vest linearly (0 = claims are paid out immediately)
+    pub vesting_duration: i64,
+    // Share of the unvested amount forfeited on early exit (in basis points)
+    pub early_exit_penalty_bps: u64,
+    // Claimed rewards locked in vesting escrows; the reward vault holds them until released,
+    // so claims and commission cannot pay them out
+    pub vesting_reserved: u64,
+    // Governance cap on agent owner commission (in basis points)
+    pub max_commission_bps: u64,
+    // Seconds a commission increase is announced before it takes effect
+    pub commission_notice_period: i64,
+    // Announced cap that replaces max_commission_bps at max_commission_effective_at
+    pub pending_max_commission_bps: u64,
+    // When the pending cap takes effect (0 = no change pending)
+    pub max_commission_effective_at: i64,
+    // Minimum seconds between two evolutions of the same agent
+    pub min_evolution_interval: i64,
+    // Seconds without a heartbeat after which anyone can jail an agent (0 = jailing disabled)
+    pub liveness_window: i64,
+    // Minimum seconds an agent stays jailed before its owner can unjail it
+    pub min_jail_duration: i64,
+    // Token account receiving slashed stake (default = slashing disabled)
+    pub slash_treasury: Pubkey,
+    // Seconds an agent owner has to appeal a slash before it can be finalized
+    pub slash_dispute_window: i64,
+    // Seconds governance has to decide an appeal; afterwards the slash lapses
+    pub slash_resolution_window: i64,
+    // Largest share of an agent's delegated stake a single slash can take (in basis points)
+    pub max_slash_bps: u64,
+    // Minimum seconds between two redelegations of the same position
+    pub redelegation_cooldown: i64,
+    // Epochs new stake waits before it can start activating
+    pub warmup_epochs: u64,
+    // Epochs unstaked stake cools down; tickets release no earlier than the start of the
+    // epoch this many epochs after the one the stake finished deactivating in
+    pub cooldown_epochs: u64,
+    // Share of total stake that can activate, and deactivate, per epoch (in basis points, 0 = no cap)
+    pub activation_cap_bps: u64,
+    // Epoch the activation budget and rate belong to (None until the first activation)
+    pub activation_budget_epoch: Option<u64>,
+    // Stake that can still activate in activation_budget_epoch
+    pub activation_budget: u64,
+    // Share of its activating stake every position activates in activation_budget_epoch,
+    // scaled by REWARD_PRECISION
+    pub activation_rate: u128,
+    // Stake activating or still warming up across all positions
+    pub total_activating: u64,
+    // Last epoch unstaked stake is queued to deactivate in
+    pub deactivation_epoch: u64,
+    // Stake already queued to deactivate in deactivation_epoch
+    pub deactivation_queued: u64,
+    // Number of agents registered; the next agent gets this value as its ID
+    pub agent_count: u64,
+    // Whether proposals can be created and voted on
+    pub governance_enabled: bool,
+    // Number of proposals created; the next proposal gets this value as its ID
+    pub proposal_count: u64,
     // Bump seed for PDA derivation
     pub bump: u8,
 }
 
 impl PlatformConfig {
     // Initialize the platform configuration with default values
-    pub fn init(&mut self, admin: Pubkey, reward_rate_bps: u64, min_stake_amount: u64, epoch_duration: i64, bump: u8) {
+    pub fn init(&mut self, admin: Pubkey, min_stake_amount: u64, epoch_duration: i64, unbonding_period: i64, bump: u8) {
         self.admin = admin;
-        self.reward_rate_bps = reward_rate_bps;
         self.min_stake_amount = min_stake_amount;
         self.epoch_duration = epoch_duration;
+        self.epoch_origin = 0;
+        self.unbonding_period = unbonding_period;
         self.last_reward_timestamp = 0;
         self.total_staked = 0;
+        self.total_weighted_stake = 0;
+        self.stake_weight_bps = 10_000;
+        self.performance_weight_bps = 0;
+        self.total_performance_score = 0;
+        self.acc_reward_per_share = 0;
+        self.acc_reward_per_score = 0;
+        self.evaluator = admin;
+        self.reward_mint = Pubkey::default();
+        self.reward_vault_bump = 0;
+        self.stake_mint = Pubkey::default();
+        self.stake_vault_bump = 0;
+        self.reward_slots = [RewardSlot::default(); MAX_REWARD_SLOTS];
+        self.vesting_cliff = 0;
+        self.vesting_duration = 0;
+        self.early_exit_penalty_bps = 0;
+        self.vesting_reserved = 0;
+        self.max_commission_bps = 10_000;
+        self.commission_notice_period = epoch_duration;
+        self.pending_max_commission_bps = 0;
+        self.max_commission_effective_at = 0;
+        self.min_evolution_interval = 0;
+        self.liveness_window = 0;
+        self.min_jail_duration = 0;
+        self.slash_treasury = Pubkey::default();
+        self.slash_dispute_window = epoch_duration;
+        self.slash_resolution_window = epoch_duration;
+        self.max_slash_bps = 0;
+        self.redelegation_cooldown = epoch_duration;
+        self.warmup_epochs = 0;
+        self.cooldown_epochs = 0;
+        self.activation_cap_bps = 0;
+        self.activation_budget_epoch = None;
+        self.activation_budget = 0;
+        self.activation_rate = 0;
+        self.total_activating = 0;
+        self.deactivation_epoch = 0;
+        self.deactivation_queued = 0;
+        self.agent_count = 0;
+        self.governance_enabled = true;
+        self.proposal_count = 0;
         self.bump = bump;
     }
 
+    // Epoch containing `now`, counted from the emission schedule's start
+    pub fn current_epoch(&self, now: i64) -> u64 {
+        if self.epoch_duration > 0 && now > self.epoch_origin {
+            ((now - self.epoch_origin) / self.epoch_duration) as u64
+        } else {
+            0
+        }
+    }
+
+    // Timestamp `epoch` starts at
+    fn epoch_start(&self, epoch: u64) -> Result<i64> {
+        i64::try_from(epoch)
+            .ok()
+            .and_then(|v| v.checked_mul(self.epoch_duration))
+            .and_then(|v| v.checked_add(self.epoch_origin))
+            .ok_or(error!(ErrorCode::MathOverflow))
+    }
+
+    // Stake that can activate, and separately deactivate, in one epoch under the cap
+    fn epoch_stake_cap(&self) -> Result<u64> {
+        (self.total_staked as u128)
+            .checked_mul(self.activation_cap_bps as u128)
+            .map(|v| (v / 10_000) as u64)
+            .ok_or(error!(ErrorCode::MathOverflow))
+    }
+
+    // Apply new warmup, cooldown and cap settings. The current epoch's activation budget and the
+    // deactivation queue were sized under the old cap, so both are rebuilt under the new one;
+    // stake already queued keeps the withdrawal time it was given.
+    pub fn set_activation_config(&mut self, warmup_epochs: u64, cooldown_epochs: u64, activation_cap_bps: u64) {
+        self.warmup_epochs = warmup_epochs;
+        self.cooldown_epochs = cooldown_epochs;
+        self.activation_cap_bps = activation_cap_bps;
+        self.activation_budget_epoch = None;
+        self.activation_budget = 0;
+        self.activation_rate = 0;
+        self.deactivation_epoch = 0;
+        self.deactivation_queued = 0;
+    }
+
+    // Queue `amount` of unstaked stake and return the earliest time it can be withdrawn: the
+    // unbonding period, and no earlier than the start of the epoch cooldown_epochs after the
+    // one its deactivation completes in. Under the cap, stake deactivates in unstake order and
+    // at most the per-epoch cap leaves each epoch, so a large exit spreads over several epochs.
+    // Unlike native staking, unstaked stake stops earning as soon as it is queued; deactivation
+    // only delays the withdrawal.
+    pub fn schedule_unbonding(&mut self, amount: u64, now: i64) -> Result<i64> {
+        let unbonded = now.checked_add(self.unbonding_period).ok_or(ErrorCode::MathOverflow)?;
+        let deactivated = self.queue_deactivation(amount, now)?;
+        let cooled = deactivated
+            .checked_add(self.cooldown_epochs)
+            .ok_or(error!(ErrorCode::MathOverflow))
+            .and_then(|epoch| self.epoch_start(epoch))?;
+        Ok(unbonded.max(cooled))
+    }
+
+    // Add `amount` to the deactivation queue, returning the epoch the last of it deactivates in
+    fn queue_deactivation(&mut self, amount: u64, now: i64) -> Result<u64> {
+        let epoch = self.current_epoch(now);
+        if self.activation_cap_bps == 0 || amount == 0 {
+            return Ok(epoch);
+        }
+        if self.deactivation_epoch < epoch {
+            self.deactivation_epoch = epoch;
+            self.deactivation_queued = 0;
+        }
+        // Fill the last queued epoch, then as many further epochs as the rest needs
+        let cap = self.epoch_stake_cap()?.max(1);
+        let queued = self.deactivation_queued.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
+        let extra_epochs = (queued - 1) / cap;
+        self.deactivation_epoch = self.deactivation_epoch.checked_add(extra_epochs).ok_or(ErrorCode::MathOverflow)?;
+        self.deactivation_queued = queued - extra_epochs * cap;
+        Ok(self.deactivation_epoch)
+    }
+
+    // Activate the position's warmed-up stake for the current epoch. Every epoch, each position
+    // activates the same share of its activating stake: the epoch's budget over all stake
+    // activating when the epoch opened, so positions activating early cannot use up the budget
+    // before the rest. A position activates at most once per epoch and a share it does not take
+    // in its epoch is not carried over (the activate_stake crank is permissionless); the budget
+    // stays a hard cap. The caller refreshes the position's weight afterwards.
+    pub fn activate_stake(&mut self, position: &mut StakePosition, now: i64) -> Result<()> {
+        let epoch = self.current_epoch(now);
+        if position.activating_amount == 0 || epoch < position.activation_epoch {
+            return Ok(());
+        }
+        let old_activating = position.activating_amount;
+        if self.activation_cap_bps == 0 {
+            position.activating_amount = 0;
+        } else {
+            self.open_activation_epoch(epoch)?;
+            // Round up so small positions are not stranded; the budget still bounds the total
+            let activated = (old_activating as u128)
+                .checked_mul(self.activation_rate)
+                .and_then(|v| v.checked_add(REWARD_PRECISION - 1))
+                .map(|v| (v / REWARD_PRECISION) as u64)
+                .ok_or(ErrorCode::MathOverflow)?
+                .min(self.activation_budget);
+            position.activating_amount -= activated;
+            position.activation_epoch = epoch + 1;
+            self.activation_budget -= activated;
+        }
+        self.sync_activating(old_activating, position.activating_amount)
+    }
+
+    // Fix the epoch's activation budget and the share of activating stake it covers
+    fn open_activation_epoch(&mut self, epoch: u64) -> Result<()> {
+        if self.activation_budget_epoch == Some(epoch) {
+            return Ok(());
+        }
+        self.activation_budget = self.epoch_stake_cap()?;
+        self.activation_rate = if self.total_activating == 0 {
+            REWARD_PRECISION
+        } else {
+            (self.activation_budget as u128)
+                .checked_mul(REWARD_PRECISION)
+                .map(|v| (v / self.total_activating as u128).min(REWARD_PRECISION))
+                .ok_or(ErrorCode::MathOverflow)?
+        };
+        self.activation_budget_epoch = Some(epoch);
+        Ok(())
+    }
+
+    // Apply a change in one position's activating stake to the platform total
+    pub fn sync_activating(&mut self, old_activating: u64, new_activating: u64) -> Result<()> {
+        self.total_activating = self.total_activating
+            .checked_sub(old_activating)
+            .and_then(|v| v.checked_add(new_activating))
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    // Take the next sequential agent ID
+    pub fn next_agent_id(&mut self) -> Result<u64> {
+        let agent_id = self.agent_count;
+        self.agent_count = agent_id.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+        Ok(agent_id)
+    }
+
+    // Whether claimed rewards are routed into a vesting escrow
+    pub fn vesting_enabled(&self) -> bool {
+        self.vesting_duration > 0
+    }
+
+    // Part of the reward vault balance not reserved for vesting escrows
+    pub fn unreserved_rewards(&self, vault_balance: u64) -> u64 {
+        vault_balance.saturating_sub(self.vesting_reserved)
+    }
+
+    // Reserve a claimed reward locked into a vesting escrow
+    pub fn reserve_vesting(&mut self, amount: u64) -> Result<()> {
+        self.vesting_reserved = self.vesting_reserved.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    // Drop the reservation of vesting rewards that were paid out or forfeited; forfeited
+    // rewards return to the reward pool
+    pub fn release_vesting(&mut self, amount: u64) -> Result<()> {
+        self.vesting_reserved = self.vesting_reserved.checked_sub(amount).ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    // Change the commission cap. Lowering applies at once; raising is announced and only applies
+    // after the commission notice period, like an agent's own increase.
+    pub fn schedule_commission_cap(&mut self, max_commission_bps: u64, now: i64) -> Result<()> {
+        self.apply_pending_commission_cap(now);
+        if max_commission_bps <= self.max_commission_bps {
+            self.max_commission_bps = max_commission_bps;
+            self.pending_max_commission_bps = 0;
+            self.max_commission_effective_at = 0;
+        } else {
+            self.pending_max_commission_bps = max_commission_bps;
+            self.max_commission_effective_at = now
+                .checked_add(self.commission_notice_period)
+                .ok_or(ErrorCode::MathOverflow)?;
+        }
+        Ok(())
+    }
+
+    // Promote the pending commission cap once its notice period has passed
+    pub fn apply_pending_commission_cap(&mut self, now: i64) {
+        if self.max_commission_effective_at != 0 && now >= self.max_commission_effective_at {
+            self.max_commission_bps = self.pending_max_commission_bps;
+            self.pending_max_commission_bps = 0;
+            self.max_commission_effective_at = 0;
+        }
+    }
+
+    // Accrue rewards emitted by the schedule since the last update into the stake and
+    // performance accumulators. Nothing is emitted while nobody is staked.
+    pub fn accrue_rewards(&mut self, schedule: &mut EmissionSchedule, now: i64) -> Result<()> {
+        self.apply_pending_commission_cap(self.last_reward_timestamp);
+        if now <= self.last_reward_timestamp {
+            return Ok(());
+        }
+        if self.total_weighted_stake == 0 || self.epoch_duration <= 0 {
+            self.last_reward_timestamp = now;
+            return Ok(());
+        }
+        let (reward, reached) = schedule.emit(self.last_reward_timestamp, now, self.epoch_duration)?;
+        self.distribute(reward as u128)?;
+        // Slot rates run on the same clock as emission: nothing accrues before the schedule starts
+        let accrual_start = self.last_reward_timestamp.max(schedule.start_timestamp);
+        self.accrue_slot_rewards((reached - accrual_start).max(0))?;
+        self.last_reward_timestamp = reached;
+        Ok(())
+    }
+
+    // Accrue up to `now` and fail if the MAX_ACCRUAL_EPOCHS cap stopped short of it. Used before
+    // any change to weights, scores or emission parameters, so the backlog is never credited at
+    // the new values; call the accrue_rewards crank until it has caught up.
+    pub fn accrue_rewards_fully(&mut self, schedule: &mut EmissionSchedule, now: i64) -> Result<()> {
+        self.accrue_rewards(schedule, now)?;
+        require!(self.last_reward_timestamp >= now, ErrorCode::AccrualBehind);
+        Ok(())
+    }
+
+    // Accrue the additional reward mints at their flat per-epoch rates, shared by weighted stake
+    fn accrue_slot_rewards(&mut self, elapsed: i64) -> Result<()> {
+        for slot in self.reward_slots.iter_mut().filter(|slot| slot.is_active()) {
+            let per_share = (slot.rate_per_epoch as u128)
+                .checked_mul(elapsed as u128)
+                .map(|v| v / self.epoch_duration as u128)
+                .and_then(|v| v.checked_mul(REWARD_PRECISION))
+                .map(|v| v / self.total_weighted_stake as u128)
+                .ok_or(ErrorCode::MathOverflow)?;
+            slot.acc_reward_per_share = slot.acc_reward_per_share
+                .checked_add(per_share)
+                .ok_or(ErrorCode::MathOverflow)?;
+        }
+        Ok(())
+    }
+
+    // Split an emitted reward between the stake-weighted and performance-weighted accumulators
+    fn distribute(&mut self, reward: u128) -> Result<()> {
+        // Without any scored agent the performance share goes to stakers
+        let performance_reward = if self.total_performance_score > 0 {
+            reward
+                .checked_mul(self.performance_weight_bps as u128)
+                .map(|v| v / 10_000)
+                .ok_or(ErrorCode::MathOverflow)?
+        } else {
+            0
+        };
+        let stake_reward = reward - performance_reward;
+
+        // Lock multipliers change each staker's share, not the amount emitted
+        if self.total_weighted_stake > 0 {
+            let per_share = stake_reward
+                .checked_mul(REWARD_PRECISION)
+                .and_then(|v| v.checked_div(self.total_weighted_stake as u128))
+                .ok_or(ErrorCode::MathOverflow)?;
+            self.acc_reward_per_share = self.acc_reward_per_share
+                .checked_add(per_share)
+                .ok_or(ErrorCode::MathOverflow)?;
+        }
+        if performance_reward > 0 {
+            let per_score = performance_reward
+                .checked_mul(REWARD_PRECISION)
+                .and_then(|v| v.checked_div(self.total_performance_score as u128))
+                .ok_or(ErrorCode::MathOverflow)?;
+            self.acc_reward_per_score = self.acc_reward_per_score
+                .checked_add(per_score)
+                .ok_or(ErrorCode::MathOverflow)?;
+        }
+        Ok(())
+    }
+
+    // Apply a change in one position's weight to the agent and platform totals.
+    // The agent must already be settled against the current accumulators.
+    pub fn apply_position_weight_change(&mut self, agent: &mut AiAgent, old_weight: u64, new_weight: u64) -> Result<()> {
+        let old_reward_weight = agent.reward_weight();
+        let old_score = agent.effective_score();
+        agent.weighted_stake = agent.weighted_stake
+            .checked_sub(old_weight)
+            .and_then(|v| v.checked_add(new_weight))
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.apply_reward_weight_change(old_reward_weight, agent.reward_weight())?;
+        self.apply_score_change(old_score, agent.effective_score())?;
+        agent.reset_reward_debts(self)
+    }
+
+    // Jail or unjail an agent, taking its weight and score out of (or back into) the platform
+    // totals and freezing (or resuming) its view of the slot accumulators.
+    // The agent must already be settled against the current accumulators.
+    pub fn set_agent_jailed(&mut self, agent: &mut AiAgent, jailed: bool, now: i64) -> Result<()> {
+        let old_reward_weight = agent.reward_weight();
+        let old_score = agent.effective_score();
+        for (index, slot) in self.reward_slots.iter().enumerate() {
+            if jailed {
+                agent.jailed_slot_accs[index] = slot.acc_reward_per_share;
+            } else {
+                // Skip what the slot accrued while the agent was jailed
+                agent.slot_acc_offsets[index] = slot.acc_reward_per_share
+                    .checked_sub(agent.jailed_slot_accs[index])
+                    .and_then(|v| v.checked_add(agent.slot_acc_offsets[index]))
+                    .ok_or(ErrorCode::MathOverflow)?;
+            }
+        }
+        agent.jailed = jailed;
+        if jailed {
+            agent.jailed_at = now;
+        }
+        self.apply_reward_weight_change(old_reward_weight, agent.reward_weight())?;
+        self.apply_score_change(old_score, agent.effective_score())?;
+        agent.reset_reward_debts(self)
+    }
+
+    // Replace an agent's old reward weight with its new one in the platform total
+    fn apply_reward_weight_change(&mut self, old_weight: u64, new_weight: u64) -> Result<()> {
+        self.total_weighted_stake = self.total_weighted_stake
+            .checked_sub(old_weight)
+            .and_then(|v| v.checked_add(new_weight))
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    // Replace an agent's old effective score with its new one in the platform total
+    pub fn apply_score_change(&mut self, old_score: u64, new_score: u64) -> Result<()> {
+        self.total_performance_score = self.total_performance_score
+            .checked_sub(old_score)
+            .and_then(|v| v.checked_add(new_score))
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
     // Calculate space required for the account
     pub const SPACE: usize = 8 + // discriminator
         32 + // admin (Pubkey)
-        8 + // reward_rate_bps (u64)
         8 + // min_stake_amount (u64)
         8 + // epoch_duration (i64)
+        8 + // epoch_origin (i64)
+        8 + // unbonding_period (i64)
         8 + // last_reward_timestamp (i64)
         8 + // total_staked (u64)
+        8 + // total_weighted_stake (u64)
+        8 + // stake_weight_bps (u64)
+        8 + // performance_weight_bps (u64)
+        8 + // total_performance_score (u64)
+        16 + // acc_reward_per_share (u128)
+        16 + // acc_reward_per_score (u128)
+        32 + // evaluator (Pubkey)
+        32 + // reward_mint (Pubkey)
+        1 + // reward_vault_bump (u8)
+        32 + // stake_mint (Pubkey)
+        1 + // stake_vault_bump (u8)
+        RewardSlot::SIZE * MAX_REWARD_SLOTS + // reward_slots
+        8 + // vesting_cliff (i64)
+        8 + // vesting_duration (i64)
+        8 + // early_exit_penalty_bps (u64)
+        8 + // vesting_reserved (u64)
+        8 + // max_commission_bps (u64)
+        8 + // commission_notice_period (i64)
+        8 + // pending_max_commission_bps (u64)
+        8 + // max_commission_effective_at (i64)
+        8 + // min_evolution_interval (i64)
+        8 + // liveness_window (i64)
+        8 + // min_jail_duration (i64)
+        32 + // slash_treasury (Pubkey)
+        8 + // slash_dispute_window (i64)
+        8 + // slash_resolution_window (i64)
+        8 + // max_slash_bps (u64)
+        8 + // redelegation_cooldown (i64)
+        8 + // warmup_epochs (u64)
+        8 + // cooldown_epochs (u64)
+        8 + // activation_cap_bps (u64)
+        1 + 8 + // activation_budget_epoch (Option<u64>)
+        8 + // activation_budget (u64)
+        16 + // activation_rate (u128)
+        8 + // total_activating (u64)
+        8 + // deactivation_epoch (u64)
+        8 + // deactivation_queued (u64)
+        8 + // agent_count (u64)
+        1 + // governance_enabled (bool)
+        8 + // proposal_count (u64)
+        1; // bump (u8)
+}
+
+// Lifecycle of an AI agent
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AgentStatus {
+    // Registered but not yet accepting stake
+    #[default]
+    Pending,
+    // Accepting stake and earning rewards
+    Active,
+    // Temporarily not accepting new stake
+    Paused,
+    // Winding down: no new stake, delegators are forced into unbonding
+    Retiring,
+    // Fully drained; the account can be closed
+    Retired,
+}
+
+impl AgentStatus {
+    pub fn can_transition_to(&self, next: AgentStatus) -> bool {
+        use AgentStatus::*;
+        matches!(
+            (self, next),
+            (Pending, Active)
+                | (Active, Paused)
+                | (Paused, Active)
+                | (Pending, Retiring)
+                | (Active, Retiring)
+                | (Paused, Retiring)
+                | (Retiring, Retired)
+        )
+    }
+}
+
+// Additional reward mint paid to stakers alongside the primary reward
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RewardSlot {
+    // Mint of the reward token (default when the slot is unused)
+    pub mint: Pubkey,
+    // Token units emitted per epoch
+    pub rate_per_epoch: u64,
+    // Rewards accrued per weighted staked token, scaled by REWARD_PRECISION
+    pub acc_reward_per_share: u128,
+    // Bump seed of the slot's reward vault PDA
+    pub vault_bump: u8,
+}
+
+impl RewardSlot {
+    pub const SIZE: usize = 32 + 8 + 16 + 1;
+
+    // Whether the slot has a mint assigned
+    pub fn is_active(&self) -> bool {
+        self.mint != Pubkey::default()
+    }
+}
+
+// One segment of a piecewise emission curve: `rate` applies from `start_epoch` onwards
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct EmissionSegment {
+    pub start_epoch: u64,
+    pub rate: u64,
+}
+
+// Shape of the reward emission over time; rates are token units emitted per epoch
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum EmissionCurve {
+    // The same rate every epoch
+    Constant { rate: u64 },
+    // Rate decreases by `decay_per_epoch` every epoch until it reaches `floor_rate`
+    LinearDecay { initial_rate: u64, decay_per_epoch: u64, floor_rate: u64 },
+    // Rate halves every `halving_interval` epochs
+    Halving { initial_rate: u64, halving_interval: u64 },
+    // Rate of the last segment whose start epoch has been reached (zero before the first)
+    Piecewise { segments: Vec<EmissionSegment> },
+}
+
+impl Default for EmissionCurve {
+    fn default() -> Self {
+        EmissionCurve::Constant { rate: 0 }
+    }
+}
+
+impl EmissionCurve {
+    // Check the curve parameters are usable
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            EmissionCurve::LinearDecay { initial_rate, floor_rate, .. } => {
+                require!(floor_rate <= initial_rate, ErrorCode::InvalidEmissionCurve);
+            }
+            EmissionCurve::Halving { halving_interval, .. } => {
+                require!(*halving_interval > 0, ErrorCode::InvalidEmissionCurve);
+            }
+            EmissionCurve::Piecewise { segments } => {
+                require!(
+                    !segments.is_empty() && segments.len() <= MAX_EMISSION_SEGMENTS,
+                    ErrorCode::InvalidEmissionCurve
+                );
+                require!(
+                    segments.windows(2).all(|pair| pair[0].start_epoch < pair[1].start_epoch),
+                    ErrorCode::InvalidEmissionCurve
+                );
+            }
+            EmissionCurve::Constant { .. } => {}
+        }
+        Ok(())
+    }
+
+    // Tokens emitted during the given epoch
+    pub fn rate_for_epoch(&self, epoch: u64) -> u64 {
+        match self {
+            EmissionCurve::Constant { rate } => *rate,
+            EmissionCurve::LinearDecay { initial_rate, decay_per_epoch, floor_rate } => initial_rate
+                .saturating_sub(decay_per_epoch.saturating_mul(epoch))
+                .max(*floor_rate),
+            EmissionCurve::Halving { initial_rate, halving_interval } => {
+                let halvings = epoch / (*halving_interval).max(1);
+                if halvings >= 64 {
+                    0
+                } else {
+                    initial_rate >> halvings
+                }
+            }
+            EmissionCurve::Piecewise { segments } => segments
+                .iter()
+                .rev()
+                .find(|segment| segment.start_epoch <= epoch)
+                .map(|segment| segment.rate)
+                .unwrap_or(0),
+        }
+    }
+}
+
+// Reward emission schedule with a hard cap on total supply emitted
+#[account]
+#[derive(Default)]
+pub struct EmissionSchedule {
+    // Emission curve
+    pub curve: EmissionCurve,
+    // Timestamp at which epoch 0 of the schedule starts
+    pub start_timestamp: i64,
+    // Maximum amount that can ever be emitted
+    pub supply_cap: u64,
+    // Amount emitted so far
+    pub total_emitted: u64,
+    // Bump seed for PDA derivation
+    pub bump: u8,
+}
+
+impl EmissionSchedule {
+    // Initialize the emission schedule
+    pub fn init(&mut self, curve: EmissionCurve, start_timestamp: i64, supply_cap: u64, bump: u8) {
+        self.curve = curve;
+        self.start_timestamp = start_timestamp;
+        self.supply_cap = supply_cap;
+        self.total_emitted = 0;
+        self.bump = bump;
+    }
+
+    // Emit rewards for the period [from, to), prorating partial epochs. Walks at most
+    // MAX_ACCRUAL_EPOCHS epochs and returns the amount emitted and the timestamp reached.
+    pub fn emit(&mut self, from: i64, to: i64, epoch_duration: i64) -> Result<(u64, i64)> {
+        require!(epoch_duration > 0, ErrorCode::InvalidEmissionCurve);
+        let mut cursor = from.max(self.start_timestamp);
+        if cursor >= to {
+            return Ok((0, to));
+        }
+
+        let mut emitted: u128 = 0;
+        let mut epochs_walked = 0;
+        while cursor < to && epochs_walked < MAX_ACCRUAL_EPOCHS {
+            let epoch = ((cursor - self.start_timestamp) / epoch_duration) as u64;
+            let epoch_end = self.start_timestamp + (epoch as i64 + 1) * epoch_duration;
+            let segment_end = epoch_end.min(to);
+            emitted = (self.curve.rate_for_epoch(epoch) as u128)
+                .checked_mul((segment_end - cursor) as u128)
+                .map(|v| v / epoch_duration as u128)
+                .and_then(|v| v.checked_add(emitted))
+                .ok_or(ErrorCode::MathOverflow)?;
+            cursor = segment_end;
+            epochs_walked += 1;
+        }
+
+        // Enforce the hard supply cap
+        let remaining = self.supply_cap.saturating_sub(self.total_emitted) as u128;
+        let emitted = emitted.min(remaining) as u64;
+        self.total_emitted = self.total_emitted.checked_add(emitted).ok_or(ErrorCode::MathOverflow)?;
+        Ok((emitted, cursor))
+    }
+
+    // Calculate space required for the account
+    pub const SPACE: usize = 8 + // discriminator
+        1 + 4 + (16 * MAX_EMISSION_SEGMENTS) + // curve (largest variant: Piecewise segments)
+        8 + // start_timestamp (i64)
+        8 + // supply_cap (u64)
+        8 + // total_emitted (u64)
         1; // bump (u8)
 }
 
@@ -54,16 +794,93 @@ impl PlatformConfig {
 pub struct AiAgent {
     // Unique identifier for the agent
     pub agent_id: u64,
-    // Owner of the agent (user who registered it)
+    // Owner of the agent (user who registered it); fixed, as it is part of the PDA seeds
     pub owner: Pubkey,
+    // Mint of the 1/1 ownership NFT; once set, whoever holds it controls the agent
+    pub ownership_mint: Pubkey,
+    // Hot key allowed to run the agent (heartbeats, evolution) but not to move stake or change
+    // ownership. `Pubkey::default()` when unset
+    pub operator: Pubkey,
+    // Controller that appointed the operator; the operator lapses once they no longer control the agent
+    pub operator_set_by: Pubkey,
     // Name of the AI agent (e.g., "Ontora-Alpha")
     pub name: String,
     // Description or metadata about the agent's purpose
     pub description: String,
-    // Total amount staked on this agent
+    // Total amount delegated to this agent by all stakers (including the owner)
     pub staked_amount: u64,
-    // Performance score (e.g., based on accuracy or tasks completed)
+    // Share of delegators' rewards paid to the owner (in basis points)
+    pub commission_bps: u64,
+    // Commission earned by the owner and not yet claimed
+    pub accrued_commission: u64,
+    // Commission earned by the owner in each reward slot and not yet claimed
+    pub accrued_slot_commission: [u64; MAX_REWARD_SLOTS],
+    // Rewards settled to positions on this agent and not yet claimed
+    pub unclaimed_rewards: u64,
+    // Slot rewards settled to positions on this agent and not yet claimed
+    pub unclaimed_slot_rewards: [u64; MAX_REWARD_SLOTS],
+    // Announced commission that replaces commission_bps at commission_effective_at
+    pub pending_commission_bps: u64,
+    // When the pending commission takes effect (0 = no change pending)
+    pub commission_effective_at: i64,
+    // Sum of the weighted amounts of all positions on this agent
+    pub weighted_stake: u64,
+    // Rewards accrued per weighted token staked on this agent after commission, scaled by REWARD_PRECISION
+    pub acc_reward_per_share: u128,
+    // reward weight * PlatformConfig.acc_reward_per_share at the last settlement
+    pub stake_reward_debt: u128,
+    // effective score * PlatformConfig.acc_reward_per_score at the last settlement
+    pub score_reward_debt: u128,
+    // Performance score set by the evaluator (e.g., based on accuracy or tasks completed)
     pub performance_score: u64,
+    // Lifecycle state; only Active agents accept new stake
+    pub status: AgentStatus,
+    // Timestamp of the last status change
+    pub status_changed_at: i64,
+    // Number of metadata updates since registration
+    pub version: u32,
+    // Hashes of prior (name, description) pairs, oldest first, bounded by MAX_METADATA_HISTORY
+    pub metadata_history: Vec<[u8; 32]>,
+    // Hash of the agent's current behavior or personality definition
+    pub behavior_hash: [u8; 32],
+    // Hash of the model the agent currently runs
+    pub model_hash: [u8; 32],
+    // Version of the model; never decreases and increases whenever the model changes
+    pub model_version: u16,
+    // Number of evolution chapters so far
+    pub chapter_count: u32,
+    // Total user interactions reported at the last evolution
+    pub interaction_count: u64,
+    // Timestamp of the last evolution (0 = never evolved)
+    pub last_evolution_timestamp: i64,
+    // Slot of the last heartbeat
+    pub last_heartbeat_slot: u64,
+    // Timestamp of the last heartbeat (registration time until the first one)
+    pub last_heartbeat_timestamp: i64,
+    // Jailed agents earn no rewards and accept no stake until their owner unjails them
+    pub jailed: bool,
+    // Timestamp the agent was last jailed
+    pub jailed_at: i64,
+    // Slot accrual skipped while jailed, subtracted from the platform's slot accumulators
+    pub slot_acc_offsets: [u128; MAX_REWARD_SLOTS],
+    // Platform slot accumulators at the time the agent was jailed
+    pub jailed_slot_accs: [u128; MAX_REWARD_SLOTS],
+    // Gross slot accumulators (see `gross_slot_accumulators`) at the last settlement
+    pub slot_acc_views: [u128; MAX_REWARD_SLOTS],
+    // Slot rewards accrued per weighted token staked on this agent after commission, scaled by REWARD_PRECISION
+    pub slot_acc_per_share: [u128; MAX_REWARD_SLOTS],
+    // Number of positions with a non-zero amount on this agent
+    pub position_count: u64,
+    // Number of slash proposals raised against this agent; the next one gets this value as its ID
+    pub slash_proposal_count: u32,
+    // Number of slashes finalized against this agent
+    pub slash_count: u32,
+    // A slash is proposed, under appeal or being applied; stake cannot move in or out
+    pub slash_pending: bool,
+    // Positions on other agents still exposed to this agent's slashes after a redelegation
+    pub outgoing_exposures: u64,
+    // Unbonding tickets from this agent not yet withdrawn; they stay exposed to its slashes
+    pub unbonding_tickets: u64,
     // Timestamp when the agent was registered
     pub created_at: i64,
     // Bump seed for PDA derivation
@@ -71,14 +888,371 @@ pub struct AiAgent {
 }
 
 impl AiAgent {
+    // Score counted towards performance rewards; agents without stake and jailed agents earn none
+    pub fn effective_score(&self) -> u64 {
+        if self.weighted_stake > 0 && !self.jailed {
+            self.performance_score
+        } else {
+            0
+        }
+    }
+
+    // Weighted stake counted towards the platform total; jailed agents count for nothing
+    pub fn reward_weight(&self) -> u64 {
+        if self.jailed {
+            0
+        } else {
+            self.weighted_stake
+        }
+    }
+
+    // Platform slot accumulators as seen by this agent: frozen while jailed and excluding
+    // everything accrued during earlier jail periods
+    pub fn gross_slot_accumulators(&self, platform: &PlatformConfig) -> Result<[u128; MAX_REWARD_SLOTS]> {
+        let mut accs = [0u128; MAX_REWARD_SLOTS];
+        for (index, slot) in platform.reward_slots.iter().enumerate() {
+            let acc = if self.jailed {
+                self.jailed_slot_accs[index]
+            } else {
+                slot.acc_reward_per_share
+            };
+            accs[index] = acc.checked_sub(self.slot_acc_offsets[index]).ok_or(ErrorCode::MathOverflow)?;
+        }
+        Ok(accs)
+    }
+
+    // Pull the agent's share of platform rewards into its own per-share accumulators, taking the
+    // owner's commission at the rate in force over the settled period. Commission is taken on all
+    // stake on the agent; on the owner's own stake it simply comes back as commission.
+    // The platform must already be accrued up to the current time.
+    pub fn settle_rewards(&mut self, platform: &PlatformConfig) -> Result<()> {
+        let stake_earned = (self.reward_weight() as u128)
+            .checked_mul(platform.acc_reward_per_share)
+            .map(|v| v / REWARD_PRECISION)
+            .and_then(|v| v.checked_sub(self.stake_reward_debt))
+            .ok_or(ErrorCode::MathOverflow)?;
+        let score_earned = (self.effective_score() as u128)
+            .checked_mul(platform.acc_reward_per_score)
+            .map(|v| v / REWARD_PRECISION)
+            .and_then(|v| v.checked_sub(self.score_reward_debt))
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.clamp_commission(platform.max_commission_bps);
+        let commission_bps = self.commission_bps;
+        if self.weighted_stake > 0 {
+            let earned = stake_earned.checked_add(score_earned).ok_or(ErrorCode::MathOverflow)?;
+            let commission = earned
+                .checked_mul(commission_bps as u128)
+                .map(|v| v / 10_000)
+                .ok_or(ErrorCode::MathOverflow)?;
+            self.accrued_commission = u64::try_from(commission)
+                .ok()
+                .and_then(|v| self.accrued_commission.checked_add(v))
+                .ok_or(ErrorCode::MathOverflow)?;
+            let per_share = (earned - commission)
+                .checked_mul(REWARD_PRECISION)
+                .map(|v| v / self.weighted_stake as u128)
+                .ok_or(ErrorCode::MathOverflow)?;
+            self.acc_reward_per_share = self.acc_reward_per_share
+                .checked_add(per_share)
+                .ok_or(ErrorCode::MathOverflow)?;
+        }
+        self.settle_slot_rewards(platform, commission_bps)?;
+
+        // An announced increase only applies to periods settled after it took effect, so it is
+        // never charged retroactively
+        self.apply_pending_commission(platform.last_reward_timestamp);
+        self.reset_reward_debts(platform)
+    }
+
+    // Pull the slot rewards accrued since the last settlement into the agent's per-share slot
+    // accumulators, taking the owner's commission on them like on the primary reward
+    fn settle_slot_rewards(&mut self, platform: &PlatformConfig, commission_bps: u64) -> Result<()> {
+        let views = self.gross_slot_accumulators(platform)?;
+        for (index, view) in views.iter().enumerate() {
+            let per_share = view.checked_sub(self.slot_acc_views[index]).ok_or(ErrorCode::MathOverflow)?;
+            self.slot_acc_views[index] = *view;
+            if per_share == 0 || self.weighted_stake == 0 {
+                continue;
+            }
+            let commission_per_share = per_share
+                .checked_mul(commission_bps as u128)
+                .map(|v| v / 10_000)
+                .ok_or(ErrorCode::MathOverflow)?;
+            let commission = commission_per_share
+                .checked_mul(self.weighted_stake as u128)
+                .map(|v| v / REWARD_PRECISION)
+                .and_then(|v| u64::try_from(v).ok())
+                .ok_or(ErrorCode::MathOverflow)?;
+            self.accrued_slot_commission[index] = self.accrued_slot_commission[index]
+                .checked_add(commission)
+                .ok_or(ErrorCode::MathOverflow)?;
+            self.slot_acc_per_share[index] = self.slot_acc_per_share[index]
+                .checked_add(per_share - commission_per_share)
+                .ok_or(ErrorCode::MathOverflow)?;
+        }
+        Ok(())
+    }
+
+    // Re-anchor the agent's debts after its weighted stake or score changed
+    pub fn reset_reward_debts(&mut self, platform: &PlatformConfig) -> Result<()> {
+        self.stake_reward_debt = (self.reward_weight() as u128)
+            .checked_mul(platform.acc_reward_per_share)
+            .map(|v| v / REWARD_PRECISION)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.score_reward_debt = (self.effective_score() as u128)
+            .checked_mul(platform.acc_reward_per_score)
+            .map(|v| v / REWARD_PRECISION)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    // Settle a position on this agent against its accumulators, recording what it earned as
+    // owed until claimed. Must be called before the position's weighted amount changes.
+    pub fn settle_position(&mut self, position: &mut StakePosition) -> Result<()> {
+        let pending = position.pending_rewards;
+        let slot_pending = position.slot_pending_rewards;
+        position.settle_rewards(self.acc_reward_per_share)?;
+        position.settle_slot_rewards(&self.slot_acc_per_share)?;
+
+        self.unclaimed_rewards = self.unclaimed_rewards
+            .checked_add(position.pending_rewards - pending)
+            .ok_or(ErrorCode::MathOverflow)?;
+        for (index, earned_before) in slot_pending.iter().enumerate() {
+            self.unclaimed_slot_rewards[index] = self.unclaimed_slot_rewards[index]
+                .checked_add(position.slot_pending_rewards[index] - earned_before)
+                .ok_or(ErrorCode::MathOverflow)?;
+        }
+        Ok(())
+    }
+
+    // Record that a position claimed `amount` of its settled rewards
+    pub fn record_reward_claim(&mut self, amount: u64) -> Result<()> {
+        self.unclaimed_rewards = self.unclaimed_rewards.checked_sub(amount).ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    // Record that a position claimed `amount` of its settled rewards in a reward slot
+    pub fn record_slot_reward_claim(&mut self, index: usize, amount: u64) -> Result<()> {
+        self.unclaimed_slot_rewards[index] = self.unclaimed_slot_rewards[index]
+            .checked_sub(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    // Whether delegators or the owner still have rewards to claim from this agent
+    pub fn has_unclaimed_rewards(&self) -> bool {
+        self.unclaimed_rewards > 0
+            || self.accrued_commission > 0
+            || self.unclaimed_slot_rewards.iter().chain(self.accrued_slot_commission.iter()).any(|amount| *amount > 0)
+    }
+
+    // Whether a slash would have anything to take: delegated stake, stake redelegated away
+    // that is still exposed, or unstaked tokens still unbonding
+    pub fn has_slashable_stake(&self) -> bool {
+        self.staked_amount > 0 || self.outgoing_exposures > 0 || self.unbonding_tickets > 0
+    }
+
+    // Change the commission. Decreases apply at once; increases are announced and only
+    // apply after the notice period so delegators can leave first.
+    pub fn schedule_commission(&mut self, commission_bps: u64, now: i64, notice_period: i64) -> Result<()> {
+        self.apply_pending_commission(now);
+        if commission_bps <= self.commission_bps {
+            self.commission_bps = commission_bps;
+            self.pending_commission_bps = 0;
+            self.commission_effective_at = 0;
+        } else {
+            self.pending_commission_bps = commission_bps;
+            self.commission_effective_at = now.checked_add(notice_period).ok_or(ErrorCode::MathOverflow)?;
+        }
+        Ok(())
+    }
+
+    // Whether the agent has been tokenized as an ownership NFT
+    pub fn is_tokenized(&self) -> bool {
+        self.ownership_mint != Pubkey::default()
+    }
+
+    // Whether `signer` controls the agent: the registering owner until the agent is tokenized,
+    // afterwards the holder of the ownership NFT in `nft_account`
+    pub fn is_controlled_by(&self, signer: &Pubkey, nft_account: Option<&TokenAccount>) -> bool {
+        if !self.is_tokenized() {
+            return *signer == self.owner;
+        }
+        nft_account.map_or(false, |account| {
+            account.mint == self.ownership_mint && account.owner == *signer && account.amount == 1
+        })
+    }
+
+    // Whether `signer` may perform operational actions: the controller, or the assigned operator
+    // while whoever appointed it still controls the agent. For a tokenized agent the operator
+    // passes the appointing holder's NFT account, so selling the NFT revokes the operator.
+    pub fn is_operated_by(&self, signer: &Pubkey, nft_account: Option<&TokenAccount>) -> bool {
+        if self.operator != Pubkey::default() && *signer == self.operator {
+            return self.is_controlled_by(&self.operator_set_by, nft_account);
+        }
+        self.is_controlled_by(signer, nft_account)
+    }
+
+    // Record that the agent is alive
+    pub fn record_heartbeat(&mut self, slot: u64, now: i64) {
+        self.last_heartbeat_slot = slot;
+        self.last_heartbeat_timestamp = now;
+    }
+
+    // Whether the agent missed the liveness window and can be jailed. Only agents that
+    // can hold stake are jailed, and a zero window disables jailing.
+    pub fn can_be_jailed(&self, now: i64, liveness_window: i64) -> Result<bool> {
+        if self.jailed || liveness_window <= 0 || !matches!(self.status, AgentStatus::Active | AgentStatus::Paused) {
+            return Ok(false);
+        }
+        let deadline = self.last_heartbeat_timestamp.checked_add(liveness_window).ok_or(ErrorCode::MathOverflow)?;
+        Ok(now > deadline)
+    }
+
+    // Whether the owner can unjail the agent: the jail period has passed and the agent has
+    // sent a heartbeat since it was jailed
+    pub fn can_be_unjailed(&self, now: i64, min_jail_duration: i64) -> Result<bool> {
+        let release = self.jailed_at.checked_add(min_jail_duration).ok_or(ErrorCode::MathOverflow)?;
+        Ok(self.jailed && now >= release && self.last_heartbeat_timestamp > self.jailed_at)
+    }
+
+    // Move to a new lifecycle state. An agent can only retire once every delegator has been
+    // unbonded and the unbonding period has passed since it started retiring, so delegators
+    // have time to claim outstanding rewards before the account can be closed.
+    pub fn transition_to(&mut self, status: AgentStatus, now: i64, unbonding_period: i64) -> Result<()> {
+        require!(self.status.can_transition_to(status), ErrorCode::InvalidStatusTransition);
+        if status == AgentStatus::Retired {
+            require!(self.staked_amount == 0, ErrorCode::AgentNotDrained);
+            let retire_at = self.status_changed_at.checked_add(unbonding_period).ok_or(ErrorCode::MathOverflow)?;
+            require!(now >= retire_at, ErrorCode::AgentNotDrained);
+        }
+        self.status = status;
+        self.status_changed_at = now;
+        Ok(())
+    }
+
+    // Record a new evolution chapter. Chapters are numbered consecutively, interaction counts
+    // are cumulative, and a new model hash requires a higher model version.
+    #[allow(clippy::too_many_arguments)]
+    pub fn evolve(
+        &mut self,
+        behavior_hash: [u8; 32],
+        model_hash: [u8; 32],
+        model_version: u16,
+        chapter_id: u32,
+        interaction_count: u64,
+        now: i64,
+        min_interval: i64,
+    ) -> Result<()> {
+        if self.last_evolution_timestamp != 0 {
+            let next_allowed = self.last_evolution_timestamp.checked_add(min_interval).ok_or(ErrorCode::MathOverflow)?;
+            require!(now >= next_allowed, ErrorCode::EvolutionTooSoon);
+        }
+        require!(chapter_id == self.chapter_count.wrapping_add(1), ErrorCode::InvalidEvolution);
+        require!(interaction_count >= self.interaction_count, ErrorCode::InvalidEvolution);
+        if model_hash == self.model_hash {
+            require!(model_version >= self.model_version, ErrorCode::InvalidEvolution);
+        } else {
+            require!(model_version > self.model_version, ErrorCode::InvalidEvolution);
+        }
+
+        self.behavior_hash = behavior_hash;
+        self.model_hash = model_hash;
+        self.model_version = model_version;
+        self.chapter_count = chapter_id;
+        self.interaction_count = interaction_count;
+        self.last_evolution_timestamp = now;
+        Ok(())
+    }
+
+    // Hash of the current name and description
+    pub fn metadata_hash(&self) -> [u8; 32] {
+        keccak::hashv(&[self.name.as_bytes(), &[0], self.description.as_bytes()]).to_bytes()
+    }
+
+    // Replace the name and description, archiving the hash of the previous pair
+    pub fn update_metadata(&mut self, name: String, description: String) -> Result<()> {
+        require!(name.len() <= MAX_NAME_LENGTH, ErrorCode::MetadataTooLarge);
+        require!(description.len() <= MAX_DESCRIPTION_LENGTH, ErrorCode::MetadataTooLarge);
+
+        if self.metadata_history.len() == MAX_METADATA_HISTORY {
+            self.metadata_history.remove(0);
+        }
+        self.metadata_history.push(self.metadata_hash());
+        self.version = self.version.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+        self.name = name;
+        self.description = description;
+        Ok(())
+    }
+
+    // Hold the commission, and any announced increase, to the platform cap. The clamped rate is
+    // kept, so raising the cap later never raises an agent's commission by itself.
+    fn clamp_commission(&mut self, max_commission_bps: u64) {
+        self.commission_bps = self.commission_bps.min(max_commission_bps);
+        if self.commission_effective_at != 0 {
+            self.pending_commission_bps = self.pending_commission_bps.min(max_commission_bps);
+            if self.pending_commission_bps <= self.commission_bps {
+                self.pending_commission_bps = 0;
+                self.commission_effective_at = 0;
+            }
+        }
+    }
+
+    // Promote the pending commission once its notice period has passed
+    pub fn apply_pending_commission(&mut self, now: i64) {
+        if self.commission_effective_at != 0 && now >= self.commission_effective_at {
+            self.commission_bps = self.pending_commission_bps;
+            self.pending_commission_bps = 0;
+            self.commission_effective_at = 0;
+        }
+    }
+
     // Initialize a new AI agent with provided data
-    pub fn init(&mut self, agent_id: u64, owner: Pubkey, name: String, description: String, created_at: i64, bump: u8) {
+    pub fn init(&mut self, agent_id: u64, owner: Pubkey, name: String, description: String, commission_bps: u64, created_at: i64, bump: u8) {
         self.agent_id = agent_id;
         self.owner = owner;
+        self.ownership_mint = Pubkey::default();
+        self.operator = Pubkey::default();
+        self.operator_set_by = Pubkey::default();
         self.name = name;
         self.description = description;
         self.staked_amount = 0;
+        self.commission_bps = commission_bps;
+        self.accrued_commission = 0;
+        self.accrued_slot_commission = [0; MAX_REWARD_SLOTS];
+        self.unclaimed_rewards = 0;
+        self.unclaimed_slot_rewards = [0; MAX_REWARD_SLOTS];
+        self.pending_commission_bps = 0;
+        self.commission_effective_at = 0;
+        self.weighted_stake = 0;
+        self.acc_reward_per_share = 0;
+        self.stake_reward_debt = 0;
+        self.score_reward_debt = 0;
         self.performance_score = 0;
+        self.status = AgentStatus::Pending;
+        self.status_changed_at = created_at;
+        self.version = 0;
+        self.metadata_history = Vec::new();
+        self.behavior_hash = [0; 32];
+        self.model_hash = [0; 32];
+        self.model_version = 0;
+        self.chapter_count = 0;
+        self.interaction_count = 0;
+        self.last_evolution_timestamp = 0;
+        self.last_heartbeat_slot = 0;
+        self.last_heartbeat_timestamp = created_at;
+        self.jailed = false;
+        self.jailed_at = 0;
+        self.slot_acc_offsets = [0; MAX_REWARD_SLOTS];
+        self.jailed_slot_accs = [0; MAX_REWARD_SLOTS];
+        self.slot_acc_views = [0; MAX_REWARD_SLOTS];
+        self.slot_acc_per_share = [0; MAX_REWARD_SLOTS];
+        self.position_count = 0;
+        self.slash_proposal_count = 0;
+        self.slash_count = 0;
+        self.slash_pending = false;
+        self.outgoing_exposures = 0;
+        self.unbonding_tickets = 0;
         self.created_at = created_at;
         self.bump = bump;
     }
@@ -87,10 +1261,48 @@ impl AiAgent {
     pub const SPACE: usize = 8 + // discriminator
         8 + // agent_id (u64)
         32 + // owner (Pubkey)
+        32 + // ownership_mint (Pubkey)
+        32 + // operator (Pubkey)
+        32 + // operator_set_by (Pubkey)
         4 + MAX_NAME_LENGTH + // name (String with max length)
         4 + MAX_DESCRIPTION_LENGTH + // description (String with max length)
         8 + // staked_amount (u64)
+        8 + // commission_bps (u64)
+        8 + // accrued_commission (u64)
+        8 * MAX_REWARD_SLOTS + // accrued_slot_commission ([u64; MAX_REWARD_SLOTS])
+        8 + // unclaimed_rewards (u64)
+        8 * MAX_REWARD_SLOTS + // unclaimed_slot_rewards ([u64; MAX_REWARD_SLOTS])
+        8 + // pending_commission_bps (u64)
+        8 + // commission_effective_at (i64)
+        8 + // weighted_stake (u64)
+        16 + // acc_reward_per_share (u128)
+        16 + // stake_reward_debt (u128)
+        16 + // score_reward_debt (u128)
         8 + // performance_score (u64)
+        1 + // status (AgentStatus)
+        8 + // status_changed_at (i64)
+        4 + // version (u32)
+        4 + 32 * MAX_METADATA_HISTORY + // metadata_history (Vec<[u8; 32]> with max length)
+        32 + // behavior_hash ([u8; 32])
+        32 + // model_hash ([u8; 32])
+        2 + // model_version (u16)
+        4 + // chapter_count (u32)
+        8 + // interaction_count (u64)
+        8 + // last_evolution_timestamp (i64)
+        8 + // last_heartbeat_slot (u64)
+        8 + // last_heartbeat_timestamp (i64)
+        1 + // jailed (bool)
+        8 + // jailed_at (i64)
+        16 * MAX_REWARD_SLOTS + // slot_acc_offsets ([u128; MAX_REWARD_SLOTS])
+        16 * MAX_REWARD_SLOTS + // jailed_slot_accs ([u128; MAX_REWARD_SLOTS])
+        16 * MAX_REWARD_SLOTS + // slot_acc_views ([u128; MAX_REWARD_SLOTS])
+        16 * MAX_REWARD_SLOTS + // slot_acc_per_share ([u128; MAX_REWARD_SLOTS])
+        8 + // position_count (u64)
+        4 + // slash_proposal_count (u32)
+        4 + // slash_count (u32)
+        1 + // slash_pending (bool)
+        8 + // outgoing_exposures (u64)
+        8 + // unbonding_tickets (u64)
         8 + // created_at (i64)
         1; // bump (u8)
 }
@@ -101,16 +1313,18 @@ impl AiAgent {
 pub struct UserStake {
     // User public key (owner of this stake)
     pub user: Pubkey,
-    // Total amount staked by the user
+    // Total amount staked by the user across all positions
     pub staked_amount: u64,
     // Accumulated rewards (unclaimed)
     pub accumulated_rewards: u64,
-    // List of agent IDs the user has staked on
-    pub staked_agents: Vec<u64>,
+    // Number of agents the user currently has an open position on
+    pub position_count: u32,
     // Timestamp of the last stake update
     pub last_stake_update: i64,
     // Timestamp of the last reward claim
     pub last_reward_claim: i64,
+    // Sequence number used to derive the next unbonding ticket PDA
+    pub next_ticket_id: u64,
     // Bump seed for PDA derivation
     pub bump: u8,
 }
@@ -121,36 +1335,621 @@ impl UserStake {
         self.user = user;
         self.staked_amount = 0;
         self.accumulated_rewards = 0;
-        self.staked_agents = Vec::new();
+        self.position_count = 0;
         self.last_stake_update = 0;
         self.last_reward_claim = 0;
+        self.next_ticket_id = 0;
+        self.bump = bump;
+    }
+
+    // Calculate space required for the account
+    pub const SPACE: usize = 8 + // discriminator
+        32 + // user (Pubkey)
+        8 + // staked_amount (u64)
+        8 + // accumulated_rewards (u64)
+        4 + // position_count (u32)
+        8 + // last_stake_update (i64)
+        8 + // last_reward_claim (i64)
+        8 + // next_ticket_id (u64)
+        1; // bump (u8)
+}
+
+// Stake held by a user on a single agent
+#[account]
+#[derive(Default)]
+pub struct StakePosition {
+    // User who owns the position
+    pub user: Pubkey,
+    // AI agent account the stake is placed on
+    pub agent: Pubkey,
+    // Identifier of the agent (mirrors AiAgent.agent_id)
+    pub agent_id: u64,
+    // Amount staked on the agent
+    pub amount: u64,
+    // Timestamp when the position was opened
+    pub entry_time: i64,
+    // Timestamp until which the position cannot be unstaked
+    pub lock_end: i64,
+    // Reward and vote multiplier granted by the lock, in basis points
+    pub multiplier_bps: u64,
+    // Amount scaled by the lock multiplier
+    pub weighted_amount: u64,
+    // Rewards already accounted for: weighted_amount * AiAgent.acc_reward_per_share at the last settlement
+    pub reward_debt: u128,
+    // Rewards settled but not yet claimed
+    pub pending_rewards: u64,
+    // Per reward slot: weighted_amount * slot accumulator at the last settlement
+    pub slot_reward_debts: [u128; MAX_REWARD_SLOTS],
+    // Per reward slot: rewards settled but not yet claimed
+    pub slot_pending_rewards: [u64; MAX_REWARD_SLOTS],
+    // Timestamp of the last reward claim for this position
+    pub last_reward_claim: i64,
+    // Number of the agent's finalized slashes already applied to this position
+    pub slash_count: u32,
+    // Timestamp of the last redelegation into or out of this position
+    pub last_redelegated_at: i64,
+    // Agent the position was redelegated from and stays exposed to (default = none)
+    pub exposure_agent: Pubkey,
+    // Part of the amount still exposed to slashes on exposure_agent; it cannot be unstaked
+    pub exposed_amount: u64,
+    // The exposure can be released from this time on
+    pub exposure_end: i64,
+    // Number of exposure_agent's finalized slashes already applied to the exposed amount
+    pub exposure_slash_count: u32,
+    // Part of the amount still warming up; it earns no rewards and carries no votes
+    pub activating_amount: u64,
+    // Next epoch the activating amount activates in; a deposit pushes it past the warmup
+    pub activation_epoch: u64,
+    // Bump seed for PDA derivation
+    pub bump: u8,
+}
+
+impl StakePosition {
+    // Initialize a new stake position
+    pub fn init(&mut self, user: Pubkey, agent: Pubkey, agent_id: u64, entry_time: i64, bump: u8) {
+        self.user = user;
+        self.agent = agent;
+        self.agent_id = agent_id;
+        self.amount = 0;
+        self.entry_time = entry_time;
+        self.lock_end = entry_time;
+        self.multiplier_bps = BASE_MULTIPLIER_BPS;
+        self.weighted_amount = 0;
+        self.reward_debt = 0;
+        self.pending_rewards = 0;
+        self.slot_reward_debts = [0; MAX_REWARD_SLOTS];
+        self.slot_pending_rewards = [0; MAX_REWARD_SLOTS];
+        self.last_reward_claim = entry_time;
+        self.slash_count = 0;
+        self.last_redelegated_at = 0;
+        self.exposure_agent = Pubkey::default();
+        self.exposed_amount = 0;
+        self.exposure_end = 0;
+        self.exposure_slash_count = 0;
+        self.activating_amount = 0;
+        self.activation_epoch = 0;
         self.bump = bump;
     }
 
-    // Add an agent ID to the user's staked agents list
-    pub fn add_staked_agent(&mut self, agent_id: u64) -> Result<()> {
-        if self.staked_agents.len() >= MAX_AGENTS_PER_USER {
-            return err!(ErrorCode::TooManyAgents);
+    // Cut `slash_bps` of the position's amount and mark the agent's `slash_count` slashes as
+    // applied. Returns the amount taken; rewards must be settled before the amount changes.
+    pub fn apply_slash(&mut self, slash_bps: u64, slash_count: u32) -> Result<u64> {
+        let cut = (self.amount as u128)
+            .checked_mul(slash_bps as u128)
+            .map(|v| (v / 10_000) as u64)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.amount -= cut;
+        self.exposed_amount = self.exposed_amount.min(self.amount);
+        self.activating_amount = self.activating_amount.min(self.amount);
+        self.slash_count = slash_count;
+        Ok(cut)
+    }
+
+    // Amount that can be unstaked or redelegated: everything but the exposed part
+    pub fn unexposed_amount(&self) -> u64 {
+        self.amount.saturating_sub(self.exposed_amount)
+    }
+
+    // Whether the position carries exposure to another agent's slashes
+    pub fn has_exposure(&self) -> bool {
+        self.exposure_agent != Pubkey::default()
+    }
+
+    // Whether a redelegation is allowed at `now` given the cooldown
+    pub fn can_redelegate(&self, now: i64, cooldown: i64) -> Result<bool> {
+        if self.last_redelegated_at == 0 {
+            return Ok(true);
+        }
+        let next_allowed = self.last_redelegated_at.checked_add(cooldown).ok_or(ErrorCode::MathOverflow)?;
+        Ok(now >= next_allowed)
+    }
+
+    // Record stake redelegated into this position from `source`; it stays exposed to the
+    // source's slashes finalized after `slash_count` until `exposure_end`
+    pub fn record_exposure(&mut self, source: Pubkey, amount: u64, slash_count: u32, exposure_end: i64) {
+        self.exposure_agent = source;
+        self.exposed_amount = amount;
+        self.exposure_slash_count = slash_count;
+        self.exposure_end = exposure_end;
+    }
+
+    // Cut `slash_bps` of the exposed amount for a slash on the exposure agent that was
+    // proposed at `proposed_at`. Slashes proposed after the exposure ended take nothing.
+    pub fn apply_exposure_slash(&mut self, slash_bps: u64, slash_count: u32, proposed_at: i64) -> Result<u64> {
+        let cut = if proposed_at < self.exposure_end {
+            (self.exposed_amount as u128)
+                .checked_mul(slash_bps as u128)
+                .map(|v| (v / 10_000) as u64)
+                .ok_or(ErrorCode::MathOverflow)?
+        } else {
+            0
+        };
+        self.amount -= cut;
+        self.exposed_amount -= cut;
+        self.activating_amount = self.activating_amount.min(self.amount);
+        self.exposure_slash_count = slash_count;
+        Ok(cut)
+    }
+
+    // Drop the exposure once it has run out
+    pub fn clear_exposure(&mut self) {
+        self.exposure_agent = Pubkey::default();
+        self.exposed_amount = 0;
+        self.exposure_end = 0;
+        self.exposure_slash_count = 0;
+    }
+
+    // Whether the position is still locked at `now`
+    pub fn is_locked(&self, now: i64) -> bool {
+        now < self.lock_end
+    }
+
+    // Lock the position for `lock_duration` from now. An active lock is never
+    // shortened and its multiplier never reduced.
+    pub fn apply_lock(&mut self, now: i64, lock_duration: i64) -> Result<()> {
+        let multiplier = lock_multiplier_bps(lock_duration)?;
+        self.expire_lock(now);
+        let lock_end = now.checked_add(lock_duration).ok_or(ErrorCode::InvalidLockDuration)?;
+        self.lock_end = self.lock_end.max(lock_end);
+        self.multiplier_bps = self.multiplier_bps.max(multiplier);
+        Ok(())
+    }
+
+    // Drop the lock bonus once the lock has run out
+    pub fn expire_lock(&mut self, now: i64) {
+        if !self.is_locked(now) {
+            self.multiplier_bps = BASE_MULTIPLIER_BPS;
+        }
+    }
+
+    // Part of the amount that has finished warming up
+    pub fn active_amount(&self) -> u64 {
+        self.amount - self.activating_amount
+    }
+
+    // Add a deposit to the warming-up stake. Stake still activating restarts its warmup, and
+    // a position that already activated this epoch waits for the next one.
+    pub fn add_activating(&mut self, amount: u64, activation_epoch: u64) -> Result<()> {
+        self.amount = self.amount.checked_add(amount).ok_or(ErrorCode::InvalidStakeAmount)?;
+        self.activating_amount = self.activating_amount.checked_add(amount).ok_or(ErrorCode::InvalidStakeAmount)?;
+        self.activation_epoch = self.activation_epoch.max(activation_epoch);
+        Ok(())
+    }
+
+    // Remove `amount`, taking it from the stake still activating first
+    pub fn remove_stake(&mut self, amount: u64) -> Result<()> {
+        self.amount = self.amount.checked_sub(amount).ok_or(ErrorCode::InvalidStakeAmount)?;
+        self.activating_amount = self.activating_amount.saturating_sub(amount);
+        Ok(())
+    }
+
+    // Recompute the weighted amount from the active stake, returning the (old, new) weights
+    pub fn refresh_weight(&mut self) -> Result<(u64, u64)> {
+        let old_weight = self.weighted_amount;
+        let new_weight = (self.active_amount() as u128)
+            .checked_mul(self.multiplier_bps as u128)
+            .map(|v| v / BASE_MULTIPLIER_BPS as u128)
+            .and_then(|v| u64::try_from(v).ok())
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.weighted_amount = new_weight;
+        Ok((old_weight, new_weight))
+    }
+
+    // Voting weight of the position's active stake; the lock bonus only counts while locked
+    pub fn vote_weight(&self, now: i64) -> u64 {
+        if self.is_locked(now) {
+            self.weighted_amount
+        } else {
+            self.active_amount()
         }
-        if !self.staked_agents.contains(&agent_id) {
-            self.staked_agents.push(agent_id);
+    }
+
+    // Rewards accrued for the current weighted amount at the given accumulator value
+    fn accrued(&self, acc_reward_per_share: u128) -> Result<u128> {
+        (self.weighted_amount as u128)
+            .checked_mul(acc_reward_per_share)
+            .map(|v| v / REWARD_PRECISION)
+            .ok_or(error!(ErrorCode::MathOverflow))
+    }
+
+    // Move rewards earned since the last settlement into pending_rewards.
+    // Must be called before the weighted amount changes.
+    pub fn settle_rewards(&mut self, acc_reward_per_share: u128) -> Result<()> {
+        let earned = self.accrued(acc_reward_per_share)?
+            .checked_sub(self.reward_debt)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let earned = u64::try_from(earned).map_err(|_| error!(ErrorCode::MathOverflow))?;
+        self.pending_rewards = self.pending_rewards.checked_add(earned).ok_or(ErrorCode::MathOverflow)?;
+        self.reward_debt = self.accrued(acc_reward_per_share)?;
+        Ok(())
+    }
+
+    // Re-anchor the reward debt after the weighted amount changed
+    pub fn reset_reward_debt(&mut self, acc_reward_per_share: u128) -> Result<()> {
+        self.reward_debt = self.accrued(acc_reward_per_share)?;
+        Ok(())
+    }
+
+    // Settle the additional reward mints against the agent's slot accumulators
+    // (`AiAgent::slot_acc_per_share`). Must be called before the weighted amount changes.
+    pub fn settle_slot_rewards(&mut self, slot_accs: &[u128; MAX_REWARD_SLOTS]) -> Result<()> {
+        for (index, acc) in slot_accs.iter().enumerate() {
+            let accrued = self.accrued(*acc)?;
+            let earned = accrued
+                .checked_sub(self.slot_reward_debts[index])
+                .and_then(|v| u64::try_from(v).ok())
+                .ok_or(ErrorCode::MathOverflow)?;
+            self.slot_pending_rewards[index] = self.slot_pending_rewards[index]
+                .checked_add(earned)
+                .ok_or(ErrorCode::MathOverflow)?;
+            self.slot_reward_debts[index] = accrued;
         }
         Ok(())
     }
 
-    // Remove an agent ID from the user's staked agents list
-    pub fn remove_staked_agent(&mut self, agent_id: u64) {
-        self.staked_agents.retain(|&id| id != agent_id);
+    // Take as much of the pending primary reward as the vault can cover for payout; the rest
+    // stays pending for a later claim
+    pub fn take_reward(&mut self, vault_balance: u64) -> u64 {
+        let reward = self.pending_rewards.min(vault_balance);
+        self.pending_rewards -= reward;
+        reward
+    }
+
+    // Take the pending reward of one slot for payout. A vault that cannot cover it in full
+    // defers the payout: nothing is taken and the reward stays pending for a later claim.
+    pub fn take_slot_reward(&mut self, index: usize, vault_balance: u64) -> u64 {
+        let pending = self.slot_pending_rewards[index];
+        if pending == 0 || vault_balance < pending {
+            return 0;
+        }
+        self.slot_pending_rewards[index] = 0;
+        pending
+    }
+
+    // Re-anchor the slot reward debts after the weighted amount changed
+    pub fn reset_slot_reward_debts(&mut self, slot_accs: &[u128; MAX_REWARD_SLOTS]) -> Result<()> {
+        for (index, acc) in slot_accs.iter().enumerate() {
+            self.slot_reward_debts[index] = self.accrued(*acc)?;
+        }
+        Ok(())
     }
 
     // Calculate space required for the account
     pub const SPACE: usize = 8 + // discriminator
         32 + // user (Pubkey)
-        8 + // staked_amount (u64)
-        8 + // accumulated_rewards (u64)
-        4 + (8 * MAX_AGENTS_PER_USER) + // staked_agents (Vec<u64> with max length)
-        8 + // last_stake_update (i64)
+        32 + // agent (Pubkey)
+        8 + // agent_id (u64)
+        8 + // amount (u64)
+        8 + // entry_time (i64)
+        8 + // lock_end (i64)
+        8 + // multiplier_bps (u64)
+        8 + // weighted_amount (u64)
+        16 + // reward_debt (u128)
+        8 + // pending_rewards (u64)
+        16 * MAX_REWARD_SLOTS + // slot_reward_debts
+        8 * MAX_REWARD_SLOTS + // slot_pending_rewards
         8 + // last_reward_claim (i64)
+        4 + // slash_count (u32)
+        8 + // last_redelegated_at (i64)
+        32 + // exposure_agent (Pubkey)
+        8 + // exposed_amount (u64)
+        8 + // exposure_end (i64)
+        4 + // exposure_slash_count (u32)
+        8 + // activating_amount (u64)
+        8 + // activation_epoch (u64)
+        1; // bump (u8)
+}
+
+// Sum the voting power of the voter's stake positions passed as remaining accounts
+pub fn position_voting_power(voter: &Pubkey, position_infos: &[AccountInfo], now: i64) -> Result<u64> {
+    let mut seen: Vec<Pubkey> = Vec::with_capacity(position_infos.len());
+    let mut power: u64 = 0;
+    for info in position_infos {
+        // Count each position only once
+        require!(!seen.contains(info.key), ErrorCode::DuplicateStakePosition);
+        seen.push(*info.key);
+
+        let position = Account::<StakePosition>::try_from(info)?;
+        require!(position.user == *voter, ErrorCode::InvalidStakePosition);
+        power = power.checked_add(position.vote_weight(now)).ok_or(ErrorCode::InvalidStakeAmount)?;
+    }
+    Ok(power)
+}
+
+// Governance proposal, voted on with the active stake of the voters' positions
+#[account]
+#[derive(Default)]
+pub struct Proposal {
+    // Sequential ID of the proposal (part of its PDA seeds)
+    pub id: u64,
+    // Creator of the proposal
+    pub creator: Pubkey,
+    // Short title
+    pub title: String,
+    // Full description
+    pub description: String,
+    // Options voters choose from
+    pub options: Vec<String>,
+    // Vote weight counted for each option
+    pub votes: Vec<u64>,
+    // Timestamp voting opens
+    pub start_time: i64,
+    // Timestamp voting closes
+    pub end_time: i64,
+    // 0 = active, 1 = approved, 2 = rejected
+    pub status: u8,
+    // Bump seed for PDA derivation
+    pub bump: u8,
+}
+
+impl Proposal {
+    // Calculate space required for the account
+    pub const SPACE: usize = 8 + // discriminator
+        8 + // id (u64)
+        32 + // creator (Pubkey)
+        4 + MAX_PROPOSAL_TITLE_LENGTH + // title (String with max length)
+        4 + MAX_PROPOSAL_DESCRIPTION_LENGTH + // description (String with max length)
+        4 + (4 + MAX_VOTE_OPTION_LENGTH) * MAX_VOTE_OPTIONS + // options (Vec<String> with max length)
+        4 + 8 * MAX_VOTE_OPTIONS + // votes (Vec<u64> with max length)
+        8 + // start_time (i64)
+        8 + // end_time (i64)
+        1 + // status (u8)
+        1; // bump (u8)
+}
+
+// A voter's vote on a governance proposal. Created once per (proposal, voter), so a second
+// vote by the same voter fails.
+#[account]
+#[derive(Default)]
+pub struct VoteRecord {
+    // Proposal voted on
+    pub proposal: Pubkey,
+    // Voter who cast the vote
+    pub voter: Pubkey,
+    // Option voted for
+    pub vote_option: u8,
+    // Voting power counted for the option
+    pub vote_weight: u64,
+    // Timestamp of the vote
+    pub voted_at: i64,
+    // Bump seed for PDA derivation
+    pub bump: u8,
+}
+
+impl VoteRecord {
+    pub fn init(&mut self, proposal: Pubkey, voter: Pubkey, vote_option: u8, vote_weight: u64, voted_at: i64, bump: u8) {
+        self.proposal = proposal;
+        self.voter = voter;
+        self.vote_option = vote_option;
+        self.vote_weight = vote_weight;
+        self.voted_at = voted_at;
+        self.bump = bump;
+    }
+
+    // Add the vote to the proposal's per-option tally
+    pub fn tally(&self, votes: &mut [u64]) -> Result<()> {
+        let total = votes.get_mut(self.vote_option as usize).ok_or(ErrorCode::InvalidVoteOption)?;
+        *total = total.checked_add(self.vote_weight).ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    pub const SPACE: usize = 8 + // discriminator
+        32 + // proposal (Pubkey)
+        32 + // voter (Pubkey)
+        1 + // vote_option (u8)
+        8 + // vote_weight (u64)
+        8 + // voted_at (i64)
+        1; // bump (u8)
+}
+
+// Maps a sequential agent ID to its account so agents can be listed and looked up by ID alone
+#[account]
+#[derive(Default)]
+pub struct AgentIndex {
+    // ID of the agent
+    pub agent_id: u64,
+    // Address of the agent's AiAgent account
+    pub agent: Pubkey,
+    // Owner the agent was registered by (part of the agent's PDA seeds)
+    pub owner: Pubkey,
+    // Bump seed for PDA derivation
+    pub bump: u8,
+}
+
+impl AgentIndex {
+    pub fn init(&mut self, agent_id: u64, agent: Pubkey, owner: Pubkey, bump: u8) {
+        self.agent_id = agent_id;
+        self.agent = agent;
+        self.owner = owner;
+        self.bump = bump;
+    }
+
+    pub const SPACE: usize = 8 + // discriminator
+        8 + // agent_id (u64)
+        32 + // agent (Pubkey)
+        32 + // owner (Pubkey)
+        1; // bump (u8)
+}
+
+// Typed description of an agent, supplied by its owner
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct AgentMetadataParams {
+    // What the agent can do (e.g., "summarize", "trade")
+    pub capabilities: Vec<String>,
+    // Free discovery labels (e.g., "defi", "nlp")
+    pub tags: Vec<String>,
+    // Model family the agent is built on (e.g., "llama")
+    pub model_family: String,
+    // Hash of the agent's endpoint URI
+    pub endpoint_uri_hash: [u8; 32],
+    // Schema the agent accepts as input
+    pub input_schema_id: u32,
+    // Schema the agent produces as output
+    pub output_schema_id: u32,
+    // Content hash of the full off-chain manifest
+    pub manifest_hash: [u8; 32],
+}
+
+impl AgentMetadataParams {
+    pub fn validate(&self) -> Result<()> {
+        require!(self.capabilities.len() <= MAX_CAPABILITIES, ErrorCode::InvalidAgentMetadata);
+        require!(self.tags.len() <= MAX_TAGS, ErrorCode::InvalidAgentMetadata);
+        for labels in [&self.capabilities, &self.tags] {
+            for (index, label) in labels.iter().enumerate() {
+                require!(!label.is_empty() && label.len() <= MAX_LABEL_LENGTH, ErrorCode::InvalidAgentMetadata);
+                require!(!labels[..index].contains(label), ErrorCode::InvalidAgentMetadata);
+            }
+        }
+        require!(
+            !self.model_family.is_empty() && self.model_family.len() <= MAX_MODEL_FAMILY_LENGTH,
+            ErrorCode::InvalidAgentMetadata
+        );
+        require!(self.manifest_hash != [0; 32], ErrorCode::InvalidAgentMetadata);
+        Ok(())
+    }
+}
+
+// Typed metadata of an agent; the account is sized to its content and reallocated as it changes
+#[account]
+#[derive(Default)]
+pub struct AgentMetadata {
+    // Agent this metadata describes
+    pub agent: Pubkey,
+    // Typed metadata fields
+    pub params: AgentMetadataParams,
+    // Timestamp of last update
+    pub updated_at: i64,
+    // Bump seed for PDA derivation
+    pub bump: u8,
+}
+
+impl AgentMetadata {
+    pub fn init(&mut self, agent: Pubkey, params: AgentMetadataParams, updated_at: i64, bump: u8) {
+        self.agent = agent;
+        self.params = params;
+        self.updated_at = updated_at;
+        self.bump = bump;
+    }
+
+    // Space required to store the given metadata
+    pub fn space(params: &AgentMetadataParams) -> usize {
+        let labels = |values: &Vec<String>| 4 + values.iter().map(|v| 4 + v.len()).sum::<usize>();
+        8 + // discriminator
+        32 + // agent (Pubkey)
+        labels(&params.capabilities) + // capabilities (Vec<String>)
+        labels(&params.tags) + // tags (Vec<String>)
+        4 + params.model_family.len() + // model_family (String)
+        32 + // endpoint_uri_hash ([u8; 32])
+        4 + // input_schema_id (u32)
+        4 + // output_schema_id (u32)
+        32 + // manifest_hash ([u8; 32])
+        8 + // updated_at (i64)
+        1 // bump (u8)
+    }
+}
+
+// Pending withdrawal created by an unstake, redeemable once the unbonding period has passed
+#[account]
+#[derive(Default)]
+pub struct UnbondingTicket {
+    // User who requested the withdrawal
+    pub user: Pubkey,
+    // Agent account the tokens were unstaked from; they stay exposed to its slashes until release
+    pub agent: Pubkey,
+    // Agent the tokens were unstaked from
+    pub agent_id: u64,
+    // Sequence number of this ticket for the user
+    pub ticket_id: u64,
+    // Amount of tokens waiting to be withdrawn
+    pub amount: u64,
+    // Timestamp when the unstake was requested
+    pub created_at: i64,
+    // Timestamp from which the tokens can be withdrawn
+    pub release_time: i64,
+    // Number of the agent's finalized slashes already applied to this ticket
+    pub slash_count: u32,
+    // Bump seed for PDA derivation
+    pub bump: u8,
+}
+
+impl UnbondingTicket {
+    // Initialize a new unbonding ticket
+    #[allow(clippy::too_many_arguments)]
+    pub fn init(
+        &mut self,
+        user: Pubkey,
+        agent: Pubkey,
+        agent_id: u64,
+        ticket_id: u64,
+        amount: u64,
+        created_at: i64,
+        release_time: i64,
+        slash_count: u32,
+        bump: u8,
+    ) {
+        self.user = user;
+        self.agent = agent;
+        self.agent_id = agent_id;
+        self.ticket_id = ticket_id;
+        self.amount = amount;
+        self.created_at = created_at;
+        self.release_time = release_time;
+        self.slash_count = slash_count;
+        self.bump = bump;
+    }
+
+    // Whether the unbonding period has passed
+    pub fn is_mature(&self, now: i64) -> bool {
+        now >= self.release_time
+    }
+
+    // Cut `slash_bps` of the ticket for a slash on its agent proposed at `proposed_at` and mark
+    // the agent's `slash_count` slashes as applied. Tickets that had already matured when the
+    // slash was proposed take nothing. Returns the amount taken.
+    pub fn apply_slash(&mut self, slash_bps: u64, slash_count: u32, proposed_at: i64) -> Result<u64> {
+        let cut = if proposed_at < self.release_time {
+            (self.amount as u128)
+                .checked_mul(slash_bps as u128)
+                .map(|v| (v / 10_000) as u64)
+                .ok_or(ErrorCode::MathOverflow)?
+        } else {
+            0
+        };
+        self.amount -= cut;
+        self.slash_count = slash_count;
+        Ok(cut)
+    }
+
+    // Calculate space required for the account
+    pub const SPACE: usize = 8 + // discriminator
+        32 + // user (Pubkey)
+        32 + // agent (Pubkey)
+        8 + // agent_id (u64)
+        8 + // ticket_id (u64)
+        8 + // amount (u64)
+        8 + // created_at (i64)
+        8 + // release_time (i64)
+        4 + // slash_count (u32)
         1; // bump (u8)
 }
 
@@ -188,10 +1987,32 @@ impl Metadata {
 // Custom error codes for state management
 #[error_code]
 pub enum ErrorCode {
-    #[msg("Too many agents staked by user.")]
-    TooManyAgents,
     #[msg("Invalid stake amount.")]
     InvalidStakeAmount,
     #[msg("Metadata data too large.")]
     MetadataTooLarge,
+    #[msg("Stake position does not belong to the voter.")]
+    InvalidStakePosition,
+    #[msg("Stake position passed more than once.")]
+    DuplicateStakePosition,
+    #[msg("Arithmetic overflow in reward accounting.")]
+    MathOverflow,
+    #[msg("Lock duration must be zero or between 7 days and 1 year.")]
+    InvalidLockDuration,
+    #[msg("Invalid emission curve parameters.")]
+    InvalidEmissionCurve,
+    #[msg("Agent status cannot change this way.")]
+    InvalidStatusTransition,
+    #[msg("Agent still has stake or is within its unbonding period.")]
+    AgentNotDrained,
+    #[msg("Minimum interval between evolutions has not passed.")]
+    EvolutionTooSoon,
+    #[msg("Evolution chapter, interaction count or model version is out of order.")]
+    InvalidEvolution,
+    #[msg("Agent metadata is missing fields, has duplicates or exceeds its limits.")]
+    InvalidAgentMetadata,
+    #[msg("Vote option does not exist on the proposal.")]
+    InvalidVoteOption,
+    #[msg("Reward accrual is behind; run the accrue_rewards crank first.")]
+    AccrualBehind,
 }
@@ -0,0 +1,283 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+use crate::state::PlatformConfig;
+use crate::instructions::transfer_from_vault;
+use crate::events::{VestingExited, VestingReleased};
+
+// Maximum number of claims that can vest side by side in one escrow
+pub const MAX_VESTING_TRANCHES: usize = 8;
+
+// One claimed reward vesting under its own schedule
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct VestingTranche {
+    // Amount vesting under this schedule (zero when the tranche is unused)
+    pub amount: u64,
+    // Amount of this tranche already released
+    pub released: u64,
+    // Start of the schedule
+    pub start_time: i64,
+    // Nothing of the tranche is released before this time
+    pub cliff_time: i64,
+    // The tranche is fully vested at this time
+    pub end_time: i64,
+}
+
+impl VestingTranche {
+    pub const SIZE: usize = 8 + 8 + 8 + 8 + 8;
+
+    // Whether the tranche is unused
+    pub fn is_empty(&self) -> bool {
+        self.amount == 0
+    }
+
+    // Amount of the tranche vested at `now`
+    pub fn vested(&self, now: i64) -> Result<u64> {
+        if now < self.cliff_time {
+            return Ok(0);
+        }
+        if now >= self.end_time {
+            return Ok(self.amount);
+        }
+        let vested = (self.amount as u128)
+            .checked_mul((now - self.start_time) as u128)
+            .map(|v| v / (self.end_time - self.start_time) as u128)
+            .ok_or(VestingError::MathOverflow)?;
+        Ok(vested as u64)
+    }
+}
+
+// Per-user escrow of claimed rewards that vest with a cliff and linear release.
+// The tokens stay in the reward vault until released, reserved through the platform config's
+// vesting_reserved so other claims cannot pay them out; the escrow only records the entitlement.
+// Each claim vests on its own schedule, so a later claim never delays an earlier one.
+#[account]
+#[derive(Default)]
+pub struct VestingEscrow {
+    // Owner of the escrow
+    pub user: Pubkey,
+    // Claims still vesting, each under the schedule in force when it was locked
+    pub tranches: [VestingTranche; MAX_VESTING_TRANCHES],
+    // Amount of fully vested tranches that were retired but not yet released
+    pub vested_carry: u64,
+    // Bump seed for PDA derivation
+    pub bump: u8,
+}
+
+impl VestingEscrow {
+    pub fn init(&mut self, user: Pubkey, bump: u8) {
+        self.user = user;
+        self.tranches = [VestingTranche::default(); MAX_VESTING_TRANCHES];
+        self.vested_carry = 0;
+        self.bump = bump;
+    }
+
+    // Amount that can be released at `now`
+    pub fn releasable(&self, now: i64) -> Result<u64> {
+        let mut total = self.vested_carry;
+        for tranche in self.tranches.iter().filter(|tranche| !tranche.is_empty()) {
+            total = tranche.vested(now)?
+                .checked_sub(tranche.released)
+                .and_then(|v| v.checked_add(total))
+                .ok_or(VestingError::MathOverflow)?;
+        }
+        Ok(total)
+    }
+
+    // Amount still unvested at `now`
+    pub fn unvested(&self, now: i64) -> Result<u64> {
+        let mut total: u64 = 0;
+        for tranche in self.tranches.iter().filter(|tranche| !tranche.is_empty()) {
+            total = total
+                .checked_add(tranche.amount - tranche.vested(now)?)
+                .ok_or(VestingError::MathOverflow)?;
+        }
+        Ok(total)
+    }
+
+    // Add a claimed reward as a new tranche vesting from now. Fully vested tranches are
+    // retired into the carry first to free their slots; earlier tranches keep their schedules.
+    pub fn lock(&mut self, amount: u64, now: i64, cliff: i64, duration: i64) -> Result<()> {
+        require!(amount > 0, VestingError::ZeroAmount);
+        for tranche in self.tranches.iter_mut().filter(|tranche| !tranche.is_empty() && now >= tranche.end_time) {
+            self.vested_carry = self.vested_carry
+                .checked_add(tranche.amount - tranche.released)
+                .ok_or(VestingError::MathOverflow)?;
+            *tranche = VestingTranche::default();
+        }
+        let slot = self.tranches
+            .iter_mut()
+            .find(|tranche| tranche.is_empty())
+            .ok_or(VestingError::TooManyTranches)?;
+        *slot = VestingTranche {
+            amount,
+            released: 0,
+            start_time: now,
+            cliff_time: now.checked_add(cliff).ok_or(VestingError::MathOverflow)?,
+            end_time: now.checked_add(duration).ok_or(VestingError::MathOverflow)?,
+        };
+        Ok(())
+    }
+
+    // Mark everything vested so far as released and return the amount
+    pub fn release(&mut self, now: i64) -> Result<u64> {
+        let amount = self.releasable(now)?;
+        for tranche in self.tranches.iter_mut().filter(|tranche| !tranche.is_empty()) {
+            tranche.released = tranche.vested(now)?;
+            if tranche.released == tranche.amount {
+                *tranche = VestingTranche::default();
+            }
+        }
+        self.vested_carry = 0;
+        Ok(amount)
+    }
+
+    // Close out the escrow early. Returns (payout, penalty); the penalty is charged on the unvested part only.
+    pub fn exit(&mut self, now: i64, penalty_bps: u64) -> Result<(u64, u64)> {
+        let releasable = self.releasable(now)?;
+        let unvested = self.unvested(now)?;
+        let penalty = (unvested as u128)
+            .checked_mul(penalty_bps as u128)
+            .map(|v| (v / 10_000) as u64)
+            .ok_or(VestingError::MathOverflow)?;
+        let payout = releasable
+            .checked_add(unvested - penalty)
+            .ok_or(VestingError::MathOverflow)?;
+        self.tranches = [VestingTranche::default(); MAX_VESTING_TRANCHES];
+        self.vested_carry = 0;
+        Ok((payout, penalty))
+    }
+
+    pub const SPACE: usize = 8 + // discriminator
+        32 + // user (Pubkey)
+        VestingTranche::SIZE * MAX_VESTING_TRANCHES + // tranches ([VestingTranche; MAX_VESTING_TRANCHES])
+        8 + // vested_carry (u64)
+        1; // bump (u8)
+}
+
+// Open a vesting escrow for claimed rewards
+#[derive(Accounts)]
+pub struct CreateVesting<'info> {
+    #[account(
+        init,
+        payer = user,
+        space = VestingEscrow::SPACE,
+        seeds = [b"vesting-escrow", user.key().as_ref()],
+        bump
+    )]
+    pub vesting_escrow: Account<'info, VestingEscrow>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_vesting(ctx: Context<CreateVesting>) -> Result<()> {
+    ctx.accounts.vesting_escrow.init(ctx.accounts.user.key(), ctx.bumps.vesting_escrow);
+
+    msg!("Vesting escrow created for user {}", ctx.accounts.user.key());
+    Ok(())
+}
+
+// Release vested rewards, or exit early, from the reward vault
+#[derive(Accounts)]
+pub struct ReleaseVested<'info> {
+    #[account(
+        mut,
+        seeds = [b"platform-config"],
+        bump = platform_config.bump
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+    #[account(
+        mut,
+        seeds = [b"vesting-escrow", user.key().as_ref()],
+        bump = vesting_escrow.bump,
+        has_one = user @ VestingError::Unauthorized
+    )]
+    pub vesting_escrow: Account<'info, VestingEscrow>,
+    pub user: Signer<'info>,
+    #[account(
+        mut,
+        constraint = user_token_account.mint == platform_config.reward_mint @ VestingError::InvalidRewardMint
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"reward-vault"],
+        bump = platform_config.reward_vault_bump
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn release_vested(ctx: Context<ReleaseVested>) -> Result<()> {
+    let vesting_escrow = &mut ctx.accounts.vesting_escrow;
+    let clock = Clock::get()?;
+
+    let amount = vesting_escrow.release(clock.unix_timestamp)?;
+    require!(amount > 0, VestingError::NothingToRelease);
+    ctx.accounts.platform_config.release_vesting(amount)?;
+
+    transfer_from_vault(
+        &ctx.accounts.platform_config,
+        ctx.accounts.reward_vault.to_account_info(),
+        ctx.accounts.user_token_account.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        amount,
+    )?;
+
+    emit!(VestingReleased {
+        user: ctx.accounts.user.key(),
+        amount,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("User {} released {} vested rewards", ctx.accounts.user.key(), amount);
+    Ok(())
+}
+
+// Take everything out now; the penalty on the unvested part is no longer reserved and
+// returns to the reward pool
+pub fn exit_vesting_early(ctx: Context<ReleaseVested>) -> Result<()> {
+    let vesting_escrow = &mut ctx.accounts.vesting_escrow;
+    let clock = Clock::get()?;
+
+    let (payout, penalty) = vesting_escrow.exit(clock.unix_timestamp, ctx.accounts.platform_config.early_exit_penalty_bps)?;
+    require!(payout > 0 || penalty > 0, VestingError::NothingToRelease);
+    let escrowed = payout.checked_add(penalty).ok_or(VestingError::MathOverflow)?;
+    ctx.accounts.platform_config.release_vesting(escrowed)?;
+
+    if payout > 0 {
+        transfer_from_vault(
+            &ctx.accounts.platform_config,
+            ctx.accounts.reward_vault.to_account_info(),
+            ctx.accounts.user_token_account.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            payout,
+        )?;
+    }
+
+    emit!(VestingExited {
+        user: ctx.accounts.user.key(),
+        amount: payout,
+        penalty,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("User {} exited vesting early: {} paid, {} returned to the reward pool", ctx.accounts.user.key(), payout, penalty);
+    Ok(())
+}
+
+#[error_code]
+pub enum VestingError {
+    #[msg("Unauthorized access.")]
+    Unauthorized,
+    #[msg("Token account mint does not match the reward mint.")]
+    InvalidRewardMint,
+    #[msg("Nothing to release.")]
+    NothingToRelease,
+    #[msg("Math overflow.")]
+    MathOverflow,
+    #[msg("Vesting amount must be greater than zero.")]
+    ZeroAmount,
+    #[msg("Too many claims are still vesting; release or wait for one to vest fully.")]
+    TooManyTranches,
+}
@@ -326,4 +326,30 @@ mod tests {
         let result = cast_vote(&mut test_context.context, &voter, &proposal_pubkey, true).await;
         assert!(result.is_err());
     }
+
+    // A vote adds its weight to the chosen option only, and an overflowing tally is rejected
+    #[test]
+    fn test_vote_record_tally() {
+        use Eonium_ai::state::VoteRecord;
+
+        let (proposal, voter) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut votes = vec![0u64; 3];
+        let mut record = VoteRecord::default();
+        record.init(proposal, voter, 1, 700, 1_000, 255);
+        record.tally(&mut votes).unwrap();
+        assert_eq!(votes, vec![0, 700, 0]);
+
+        let mut other = VoteRecord::default();
+        other.init(proposal, Pubkey::new_unique(), 1, 300, 1_010, 254);
+        other.tally(&mut votes).unwrap();
+        assert_eq!(votes, vec![0, 1_000, 0]);
+
+        // Options outside the proposal and overflowing tallies fail instead of wrapping
+        other.vote_option = 3;
+        assert!(other.tally(&mut votes).is_err());
+        other.vote_option = 1;
+        other.vote_weight = u64::MAX;
+        assert!(other.tally(&mut votes).is_err());
+        assert_eq!(votes, vec![0, 1_000, 0]);
+    }
 }
@@ -341,6 +341,480 @@ mod tests {
         );
     }
 
+    // Stake `amount` into a position, settling the agent and position first
+    fn stake_into(
+        config: &mut Eonium_ai::state::PlatformConfig,
+        schedule: &mut Eonium_ai::state::EmissionSchedule,
+        agent: &mut Eonium_ai::state::AiAgent,
+        position: &mut Eonium_ai::state::StakePosition,
+        now: i64,
+        amount: u64,
+    ) {
+        config.accrue_rewards(schedule, now).unwrap();
+        agent.settle_rewards(config).unwrap();
+        position.settle_rewards(agent.acc_reward_per_share).unwrap();
+        position.amount += amount;
+        config.total_staked += amount;
+        let (old_weight, new_weight) = position.refresh_weight().unwrap();
+        config.apply_position_weight_change(agent, old_weight, new_weight).unwrap();
+        position.reset_reward_debt(agent.acc_reward_per_share).unwrap();
+    }
+
+    // Test case: A late staker only earns from the moment it stakes
+    #[test]
+    fn test_reward_accumulator_late_staker() {
+        use Eonium_ai::state::{
+            AiAgent, EmissionCurve, EmissionSchedule, PlatformConfig, StakePosition, BASE_MULTIPLIER_BPS,
+        };
+
+        let mut config = PlatformConfig {
+            epoch_duration: 100,
+            stake_weight_bps: 10_000,
+            ..Default::default()
+        };
+        let mut schedule = EmissionSchedule {
+            curve: EmissionCurve::Constant { rate: 200 },
+            supply_cap: u64::MAX,
+            ..Default::default()
+        };
+        let mut agent = AiAgent::default();
+        let mut early = StakePosition { multiplier_bps: BASE_MULTIPLIER_BPS, ..Default::default() };
+        let mut late = StakePosition { multiplier_bps: BASE_MULTIPLIER_BPS, ..Default::default() };
+
+        // Early staker joins at t = 0, late staker one epoch later
+        stake_into(&mut config, &mut schedule, &mut agent, &mut early, 0, 1_000);
+        stake_into(&mut config, &mut schedule, &mut agent, &mut late, 100, 1_000);
+
+        // Settle both after another epoch
+        config.accrue_rewards(&mut schedule, 200).unwrap();
+        agent.settle_rewards(&config).unwrap();
+        early.settle_rewards(agent.acc_reward_per_share).unwrap();
+        late.settle_rewards(agent.acc_reward_per_share).unwrap();
+
+        assert_eq!(early.pending_rewards, 300, "Early staker should earn all of epoch 0 and half of epoch 1");
+        assert_eq!(late.pending_rewards, 100, "Late staker should only earn the second epoch");
+    }
+
+    // Test case: The performance share of emissions follows agent scores
+    #[test]
+    fn test_performance_weighted_rewards() {
+        use Eonium_ai::state::{
+            AiAgent, EmissionCurve, EmissionSchedule, PlatformConfig, StakePosition, BASE_MULTIPLIER_BPS,
+        };
+
+        let mut config = PlatformConfig {
+            epoch_duration: 100,
+            stake_weight_bps: 5_000,
+            performance_weight_bps: 5_000,
+            ..Default::default()
+        };
+        let mut schedule = EmissionSchedule {
+            curve: EmissionCurve::Constant { rate: 200 },
+            supply_cap: u64::MAX,
+            ..Default::default()
+        };
+        let mut strong = AiAgent { performance_score: 3, ..Default::default() };
+        let mut weak = AiAgent { performance_score: 1, ..Default::default() };
+        let mut strong_position = StakePosition { multiplier_bps: BASE_MULTIPLIER_BPS, ..Default::default() };
+        let mut weak_position = StakePosition { multiplier_bps: BASE_MULTIPLIER_BPS, ..Default::default() };
+
+        stake_into(&mut config, &mut schedule, &mut strong, &mut strong_position, 0, 1_000);
+        stake_into(&mut config, &mut schedule, &mut weak, &mut weak_position, 0, 1_000);
+        assert_eq!(config.total_performance_score, 4);
+
+        // One epoch emits 200: 100 split by stake (50/50), 100 split by score (75/25)
+        config.accrue_rewards(&mut schedule, 100).unwrap();
+        strong.settle_rewards(&config).unwrap();
+        weak.settle_rewards(&config).unwrap();
+        strong_position.settle_rewards(strong.acc_reward_per_share).unwrap();
+        weak_position.settle_rewards(weak.acc_reward_per_share).unwrap();
+
+        assert_eq!(strong_position.pending_rewards, 125);
+        assert_eq!(weak_position.pending_rewards, 75);
+    }
+
+    // Test case: Emission curves and the supply cap
+    #[test]
+    fn test_emission_schedule_curves() {
+        use Eonium_ai::state::{EmissionCurve, EmissionSchedule, EmissionSegment};
+
+        let linear = EmissionCurve::LinearDecay { initial_rate: 1_000, decay_per_epoch: 300, floor_rate: 200 };
+        assert_eq!(linear.rate_for_epoch(0), 1_000);
+        assert_eq!(linear.rate_for_epoch(2), 400);
+        assert_eq!(linear.rate_for_epoch(5), 200);
+
+        let halving = EmissionCurve::Halving { initial_rate: 1_000, halving_interval: 4 };
+        assert_eq!(halving.rate_for_epoch(3), 1_000);
+        assert_eq!(halving.rate_for_epoch(4), 500);
+        assert_eq!(halving.rate_for_epoch(9), 250);
+
+        let piecewise = EmissionCurve::Piecewise {
+            segments: vec![
+                EmissionSegment { start_epoch: 1, rate: 300 },
+                EmissionSegment { start_epoch: 10, rate: 100 },
+            ],
+        };
+        assert!(piecewise.validate().is_ok());
+        assert_eq!(piecewise.rate_for_epoch(0), 0);
+        assert_eq!(piecewise.rate_for_epoch(9), 300);
+        assert_eq!(piecewise.rate_for_epoch(10), 100);
+
+        // Partial epochs are prorated, and emission stops at the supply cap
+        let mut schedule = EmissionSchedule {
+            curve: EmissionCurve::Constant { rate: 1_000 },
+            supply_cap: 1_800,
+            ..Default::default()
+        };
+        assert_eq!(schedule.emit(0, 50, 100).unwrap(), (500, 50));
+        assert_eq!(schedule.emit(50, 250, 100).unwrap(), (1_300, 250));
+        assert_eq!(schedule.total_emitted, 1_800);
+    }
+
+    // Test case: Merkle tree proofs for off-chain computed payouts
+    #[test]
+    fn test_merkle_distributor_proofs() {
+        use Eonium_ai::distributor::{hash_leaf, verify_proof, MerkleTree};
+
+        let entries: Vec<(Pubkey, u64)> = (0..5).map(|i| (Pubkey::new_unique(), 100 * (i + 1))).collect();
+        let tree = MerkleTree::from_entries(&entries);
+        let root = tree.root();
+
+        for (index, (claimant, amount)) in entries.iter().enumerate() {
+            let proof = tree.proof(index).unwrap();
+            assert!(verify_proof(&proof, &root, hash_leaf(index as u64, claimant, *amount)));
+            // A different amount or index must not verify against the same proof
+            assert!(!verify_proof(&proof, &root, hash_leaf(index as u64, claimant, *amount + 1)));
+            assert!(!verify_proof(&proof, &root, hash_leaf(index as u64 + 1, claimant, *amount)));
+        }
+        assert!(tree.proof(entries.len()).is_none());
+    }
+
+    // Test case: Vesting cliff, linear release and early exit penalty
+    #[test]
+    fn test_vesting_escrow_release() {
+        use Eonium_ai::vesting::VestingEscrow;
+
+        let mut escrow = VestingEscrow::default();
+        escrow.lock(1_000, 0, 100, 400).unwrap();
+
+        // Nothing before the cliff, then linear from the start
+        assert_eq!(escrow.releasable(99).unwrap(), 0);
+        assert_eq!(escrow.release(200).unwrap(), 500);
+        assert_eq!(escrow.releasable(200).unwrap(), 0);
+
+        // A new claim vests on its own schedule; the first keeps vesting on its original one
+        escrow.lock(500, 300, 100, 400).unwrap();
+        assert_eq!(escrow.releasable(300).unwrap(), 250);
+        assert_eq!(escrow.releasable(400).unwrap(), 500 + 125);
+        assert_eq!(escrow.unvested(400).unwrap(), 375);
+
+        // Early exit forfeits 50% of the unvested part only
+        let (payout, penalty) = escrow.exit(500, 5_000).unwrap();
+        assert_eq!(penalty, 125);
+        assert_eq!(payout, 500 + 250 + 125);
+        assert_eq!(escrow.releasable(500).unwrap(), 0);
+    }
+
+    // Test case: Locked vesting rewards are reserved in the reward vault until released or forfeited
+    #[test]
+    fn test_vesting_reservation() {
+        use Eonium_ai::vesting::VestingEscrow;
+
+        let mut config = PlatformConfig::default();
+        let mut escrow = VestingEscrow::default();
+        escrow.lock(1_000, 0, 0, 400).unwrap();
+        config.reserve_vesting(1_000).unwrap();
+
+        // Only the vault balance beyond the reservation can pay claims
+        assert_eq!(config.unreserved_rewards(1_500), 500);
+        assert_eq!(config.unreserved_rewards(800), 0);
+
+        // Releasing drops the reservation of what was paid out
+        let released = escrow.release(100).unwrap();
+        config.release_vesting(released).unwrap();
+        assert_eq!(config.vesting_reserved, 750);
+
+        // Early exit drops the whole reservation; the penalty stays in the vault, unreserved
+        let (payout, penalty) = escrow.exit(200, 5_000).unwrap();
+        config.release_vesting(payout + penalty).unwrap();
+        assert_eq!(penalty, 250);
+        assert_eq!(config.vesting_reserved, 0);
+        assert_eq!(config.unreserved_rewards(1_500 - released - payout), 1_500 - 1_000 + penalty);
+        assert!(config.release_vesting(1).is_err());
+    }
+
+    // Test case: Zero-amount locks are rejected and fully vested tranches free their slot
+    #[test]
+    fn test_vesting_escrow_tranches() {
+        use Eonium_ai::vesting::{VestingEscrow, MAX_VESTING_TRANCHES};
+
+        let mut escrow = VestingEscrow::default();
+        assert!(escrow.lock(0, 0, 0, 100).is_err());
+
+        for i in 0..MAX_VESTING_TRANCHES as i64 {
+            escrow.lock(100, i, 0, 100).unwrap();
+        }
+        assert!(escrow.lock(100, 50, 0, 100).is_err());
+
+        // Once the first tranche has vested fully its slot is reused and its balance carried
+        escrow.lock(100, 100, 0, 100).unwrap();
+        assert_eq!(escrow.vested_carry, 100);
+        assert_eq!(escrow.releasable(100).unwrap(), 100 + (1..MAX_VESTING_TRANCHES as u64).map(|i| 100 - i).sum::<u64>());
+        assert_eq!(escrow.release(300).unwrap(), 100 * (MAX_VESTING_TRANCHES as u64 + 1));
+        assert!(escrow.tranches.iter().all(|tranche| tranche.is_empty()));
+    }
+
+    // Test case: A jailed agent earns nothing, including from reward slots, until it is unjailed
+    #[test]
+    fn test_jailed_agent_stops_earning() {
+        use Eonium_ai::state::{
+            AgentStatus, AiAgent, EmissionCurve, EmissionSchedule, PlatformConfig, RewardSlot, StakePosition,
+            BASE_MULTIPLIER_BPS,
+        };
+
+        let mut config = PlatformConfig {
+            epoch_duration: 100,
+            stake_weight_bps: 10_000,
+            liveness_window: 50,
+            min_jail_duration: 50,
+            ..Default::default()
+        };
+        config.reward_slots[0] = RewardSlot { mint: Pubkey::new_unique(), rate_per_epoch: 100, ..Default::default() };
+        let mut schedule = EmissionSchedule {
+            curve: EmissionCurve::Constant { rate: 200 },
+            supply_cap: u64::MAX,
+            ..Default::default()
+        };
+        let mut idle = AiAgent { status: AgentStatus::Active, ..Default::default() };
+        let mut live = AiAgent { status: AgentStatus::Active, ..Default::default() };
+        let mut idle_position = StakePosition { multiplier_bps: BASE_MULTIPLIER_BPS, ..Default::default() };
+        let mut live_position = StakePosition { multiplier_bps: BASE_MULTIPLIER_BPS, ..Default::default() };
+
+        stake_into(&mut config, &mut schedule, &mut idle, &mut idle_position, 0, 1_000);
+        stake_into(&mut config, &mut schedule, &mut live, &mut live_position, 0, 1_000);
+
+        // Jailed at t = 100 after missing the liveness window
+        assert!(!idle.can_be_jailed(50, config.liveness_window).unwrap());
+        assert!(idle.can_be_jailed(100, config.liveness_window).unwrap());
+        config.accrue_rewards(&mut schedule, 100).unwrap();
+        idle.settle_rewards(&config).unwrap();
+        config.set_agent_jailed(&mut idle, true, 100).unwrap();
+        assert_eq!(config.total_weighted_stake, 1_000);
+
+        // Unjailing needs the jail period to pass and a fresh heartbeat
+        assert!(!idle.can_be_unjailed(200, config.min_jail_duration).unwrap());
+        idle.record_heartbeat(42, 150);
+        assert!(!idle.can_be_unjailed(149, config.min_jail_duration).unwrap());
+        assert!(idle.can_be_unjailed(200, config.min_jail_duration).unwrap());
+        config.accrue_rewards(&mut schedule, 200).unwrap();
+        idle.settle_rewards(&config).unwrap();
+        config.set_agent_jailed(&mut idle, false, 200).unwrap();
+
+        config.accrue_rewards(&mut schedule, 300).unwrap();
+        for (agent, position) in [(&mut idle, &mut idle_position), (&mut live, &mut live_position)] {
+            agent.settle_rewards(&config).unwrap();
+            position.settle_rewards(agent.acc_reward_per_share).unwrap();
+            position.settle_slot_rewards(&agent.slot_acc_per_share).unwrap();
+        }
+
+        assert_eq!(idle_position.pending_rewards, 200, "Jailed agent should miss the whole jailed epoch");
+        assert_eq!(live_position.pending_rewards, 400);
+        assert_eq!(idle_position.slot_pending_rewards[0], 100);
+        assert_eq!(live_position.slot_pending_rewards[0], 200);
+    }
+
+    // Test case: Weight changes wait until capped accrual has caught up with the clock
+    #[test]
+    fn test_accrual_backlog_blocks_weight_changes() {
+        use Eonium_ai::state::{
+            AiAgent, EmissionCurve, EmissionSchedule, PlatformConfig, StakePosition, BASE_MULTIPLIER_BPS,
+            MAX_ACCRUAL_EPOCHS,
+        };
+
+        let mut config = PlatformConfig {
+            epoch_duration: 100,
+            stake_weight_bps: 10_000,
+            ..Default::default()
+        };
+        let mut schedule = EmissionSchedule {
+            curve: EmissionCurve::Constant { rate: 200 },
+            supply_cap: u64::MAX,
+            ..Default::default()
+        };
+        let mut agent = AiAgent::default();
+        let mut position = StakePosition { multiplier_bps: BASE_MULTIPLIER_BPS, ..Default::default() };
+        stake_into(&mut config, &mut schedule, &mut agent, &mut position, 0, 1_000);
+
+        // Ten epochs past the cap: a full accrual stops short and refuses to continue
+        let now = (MAX_ACCRUAL_EPOCHS as i64 + 10) * 100;
+        assert!(config.accrue_rewards_fully(&mut schedule, now).is_err());
+        assert_eq!(config.last_reward_timestamp, MAX_ACCRUAL_EPOCHS as i64 * 100);
+
+        // The crank works through the rest, after which weight changes go ahead
+        config.accrue_rewards(&mut schedule, now).unwrap();
+        config.accrue_rewards_fully(&mut schedule, now).unwrap();
+        assert_eq!(config.last_reward_timestamp, now);
+        assert_eq!(schedule.total_emitted, (MAX_ACCRUAL_EPOCHS + 10) * 200);
+    }
+
+    // Test case: Slot rates start with the emission schedule, and an underfunded slot vault defers its payout
+    #[test]
+    fn test_slot_rewards_clamped_and_deferred() {
+        use Eonium_ai::state::{
+            AiAgent, EmissionCurve, EmissionSchedule, PlatformConfig, RewardSlot, StakePosition, BASE_MULTIPLIER_BPS,
+        };
+
+        let mut config = PlatformConfig {
+            epoch_duration: 100,
+            stake_weight_bps: 10_000,
+            ..Default::default()
+        };
+        config.reward_slots[0] = RewardSlot { mint: Pubkey::new_unique(), rate_per_epoch: 100, ..Default::default() };
+        config.reward_slots[1] = RewardSlot { mint: Pubkey::new_unique(), rate_per_epoch: 50, ..Default::default() };
+        let mut schedule = EmissionSchedule {
+            curve: EmissionCurve::Constant { rate: 200 },
+            start_timestamp: 1_000,
+            supply_cap: u64::MAX,
+            ..Default::default()
+        };
+        let mut agent = AiAgent::default();
+        let mut position = StakePosition { multiplier_bps: BASE_MULTIPLIER_BPS, ..Default::default() };
+
+        // Staked well before the schedule starts: only the epoch after the start earns
+        stake_into(&mut config, &mut schedule, &mut agent, &mut position, 0, 1_000);
+        config.accrue_rewards(&mut schedule, 1_100).unwrap();
+        agent.settle_rewards(&config).unwrap();
+        position.settle_rewards(agent.acc_reward_per_share).unwrap();
+        position.settle_slot_rewards(&agent.slot_acc_per_share).unwrap();
+        assert_eq!(position.pending_rewards, 200);
+        assert_eq!(position.slot_pending_rewards[0], 100);
+        assert_eq!(position.slot_pending_rewards[1], 50);
+
+        // Slot 0's vault is short: it stays pending while slot 1 pays out
+        assert_eq!(position.take_slot_reward(0, 99), 0);
+        assert_eq!(position.take_slot_reward(1, 50), 50);
+        assert_eq!(position.slot_pending_rewards, [100, 0, 0, 0]);
+
+        // Once topped up the deferred reward is paid in full
+        assert_eq!(position.take_slot_reward(0, 1_000), 100);
+        assert_eq!(position.take_slot_reward(0, 1_000), 0);
+        assert_eq!(position.slot_pending_rewards, [0; 4]);
+    }
+
+    // Test case: An underfunded reward vault pays what it holds and keeps the rest pending
+    #[test]
+    fn test_primary_reward_partial_payout() {
+        let mut position = StakePosition { pending_rewards: 200, ..Default::default() };
+
+        assert_eq!(position.take_reward(150), 150);
+        assert_eq!(position.pending_rewards, 50);
+
+        // An empty vault pays nothing; a topped-up one pays the remainder
+        assert_eq!(position.take_reward(0), 0);
+        assert_eq!(position.take_reward(1_000), 50);
+        assert_eq!(position.pending_rewards, 0);
+    }
+
+    // Legacy agent with `staked_amount` staked and nothing paid yet
+    fn legacy_agent(staked_amount: u64) -> Eonium_ai::AIAgent {
+        Eonium_ai::AIAgent {
+            owner: Pubkey::new_unique(),
+            staked_amount,
+            last_stake_time: 0,
+            accumulated_rewards: 0,
+            is_active: true,
+            last_rewarded_epoch: 0,
+            reward_debt: 0,
+            bump: 255,
+        }
+    }
+
+    // Legacy reward pool holding `total_rewards` for `total_staked`, at the start of epoch 0
+    fn legacy_pool(total_rewards: u64, total_staked: u64) -> Eonium_ai::RewardPool {
+        Eonium_ai::RewardPool {
+            total_rewards,
+            last_updated: 0,
+            current_epoch: 0,
+            epoch_start: 0,
+            total_staked,
+            payout_ratio_bps: 10_000,
+            outstanding_debt: 0,
+            debt_reserve: 0,
+            bump: 255,
+        }
+    }
+
+    // Test case: The crank pays each agent once per epoch and tips the keeper out of the reward
+    #[test]
+    fn test_crank_pays_once_per_epoch_with_keeper_tip() {
+        use Eonium_ai::keeper_tip;
+
+        const EPOCH: i64 = 604_800;
+        let mut pool = legacy_pool(1_000_000, 200_000);
+        let mut agent = legacy_agent(200_000);
+
+        // Nothing is due until the first epoch has run its full duration
+        assert!(pool.advance_epoch(EPOCH - 1).unwrap().is_none());
+        assert_eq!(agent.take_epoch_reward(&mut pool).unwrap(), None);
+
+        // REWARD_RATE / 1000 of the stake per epoch, paid once however often the crank runs
+        assert!(pool.advance_epoch(EPOCH).unwrap().is_none());
+        assert_eq!(pool.current_epoch, 1);
+        assert_eq!(agent.take_epoch_reward(&mut pool).unwrap(), Some(20_000));
+        assert_eq!(agent.take_epoch_reward(&mut pool).unwrap(), None);
+        assert_eq!(pool.total_rewards, 1_000_000 - 20_000);
+
+        // The keeper gets 0.5% of the reward, taken from the agent's share
+        assert_eq!(keeper_tip(20_000).unwrap(), 100);
+        assert_eq!(keeper_tip(200).unwrap(), 1);
+        assert_eq!(keeper_tip(199).unwrap(), 0);
+        assert!(keeper_tip(u64::MAX).is_err());
+
+        // Skipped epochs are not paid retroactively; the next crank pays the latest one
+        pool.advance_epoch(EPOCH * 4).unwrap();
+        assert_eq!(pool.current_epoch, 4);
+        assert_eq!(agent.take_epoch_reward(&mut pool).unwrap(), Some(20_000));
+        assert_eq!(agent.last_rewarded_epoch, 4);
+
+        // An overflowing reward is an error rather than a wrapped payout
+        pool.advance_epoch(EPOCH * 5).unwrap();
+        assert!(legacy_agent(u64::MAX).take_epoch_reward(&mut pool).is_err());
+        assert_eq!(pool.total_rewards, 1_000_000 - 40_000);
+    }
+
+    // Test case: An underfunded pool pays every agent the same share and repays the rest after top-ups
+    #[test]
+    fn test_underfunded_pool_pro_rata_and_debt_repayment() {
+        const EPOCH: i64 = 604_800;
+        // Each agent is due 10_000 per epoch, but the pool only holds 10_000 in total
+        let mut pool = legacy_pool(10_000, 200_000);
+        let mut first = legacy_agent(100_000);
+        let mut second = legacy_agent(100_000);
+
+        let degraded = pool.advance_epoch(EPOCH).unwrap().unwrap();
+        assert_eq!((degraded.payout_ratio_bps, degraded.available, degraded.required), (5_000, 10_000, 20_000));
+
+        // Crank order does not matter: both are paid half and owed the other half
+        assert_eq!(second.take_epoch_reward(&mut pool).unwrap(), Some(5_000));
+        assert_eq!(first.take_epoch_reward(&mut pool).unwrap(), Some(5_000));
+        assert_eq!((first.reward_debt, second.reward_debt), (5_000, 5_000));
+        assert_eq!((pool.total_rewards, pool.outstanding_debt), (0, 10_000));
+
+        // A top-up goes to the debt first; agents collect it as they settle
+        pool.add_funds(6_000).unwrap();
+        assert_eq!((pool.debt_reserve, pool.total_rewards), (6_000, 0));
+        assert_eq!(first.settle_debt(&mut pool).unwrap(), 5_000);
+        assert_eq!(second.settle_debt(&mut pool).unwrap(), 1_000);
+        assert_eq!(second.reward_debt, 4_000);
+
+        // Only what is still unreserved is set aside; the rest funds future epochs
+        pool.add_funds(10_000).unwrap();
+        assert_eq!((pool.debt_reserve, pool.total_rewards), (4_000, 6_000));
+        assert_eq!(second.settle_debt(&mut pool).unwrap(), 4_000);
+        assert_eq!((first.accumulated_rewards, second.accumulated_rewards), (10_000, 10_000));
+        assert_eq!((pool.outstanding_debt, pool.debt_reserve), (0, 0));
+    }
+
     // Placeholder helper functions (replace with actual program instructions)
     async fn stake_tokens(
         test_context: &mut ProgramTestContext,
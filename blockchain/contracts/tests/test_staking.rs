@@ -340,6 +340,675 @@ async fn test_multiple_users_stake_same_agent() {
     assert!(result2.is_ok());
 }
 
+// Test lock durations map to the governance lockup multipliers and active locks are never shortened
+#[test]
+fn test_lock_multipliers() {
+    use Eonium_ai::state::{lock_multiplier_bps, StakePosition, BASE_MULTIPLIER_BPS};
+
+    const DAY: i64 = 86_400;
+    assert_eq!(lock_multiplier_bps(0).unwrap(), BASE_MULTIPLIER_BPS);
+    assert_eq!(lock_multiplier_bps(7 * DAY).unwrap(), 10_000);
+    assert_eq!(lock_multiplier_bps(45 * DAY).unwrap(), 15_000);
+    assert_eq!(lock_multiplier_bps(90 * DAY).unwrap(), 20_000);
+    assert_eq!(lock_multiplier_bps(365 * DAY).unwrap(), 30_000);
+    assert!(lock_multiplier_bps(DAY).is_err());
+    assert!(lock_multiplier_bps(366 * DAY).is_err());
+
+    let mut position = StakePosition { amount: 1_000, multiplier_bps: BASE_MULTIPLIER_BPS, ..Default::default() };
+    position.apply_lock(0, 90 * DAY).unwrap();
+    position.refresh_weight().unwrap();
+    assert_eq!(position.weighted_amount, 2_000);
+    assert_eq!(position.vote_weight(DAY), 2_000);
+
+    // A shorter lock on top keeps the longer lock and higher multiplier
+    position.apply_lock(DAY, 7 * DAY).unwrap();
+    assert_eq!(position.lock_end, 90 * DAY);
+    assert_eq!(position.multiplier_bps, 20_000);
+
+    // Once expired, the bonus no longer counts for votes and is dropped on the next settlement
+    assert_eq!(position.vote_weight(90 * DAY), 1_000);
+    position.expire_lock(90 * DAY);
+    position.refresh_weight().unwrap();
+    assert_eq!(position.weighted_amount, 1_000);
+}
+
+// Test commission is taken at settlement at the rate in force, increases wait out the notice
+// period, and the governance cap always applies
+#[test]
+fn test_commission_notice_period() {
+    use Eonium_ai::state::{AiAgent, PlatformConfig, REWARD_PRECISION};
+
+    // Emit one token per weighted token on the agent, i.e. 1_000 in total, up to `now`
+    fn settle_epoch(config: &mut PlatformConfig, agent: &mut AiAgent, now: i64) {
+        config.acc_reward_per_share += REWARD_PRECISION;
+        config.last_reward_timestamp = now;
+        agent.settle_rewards(config).unwrap();
+    }
+
+    let mut config = PlatformConfig { max_commission_bps: 10_000, ..Default::default() };
+    let mut agent = AiAgent { commission_bps: 1_000, weighted_stake: 1_000, ..Default::default() };
+
+    // An increase is only announced; the period it took effect in is still charged at the old rate
+    agent.schedule_commission(2_000, 100, 50).unwrap();
+    settle_epoch(&mut config, &mut agent, 149);
+    assert_eq!(agent.accrued_commission, 100);
+    settle_epoch(&mut config, &mut agent, 160);
+    assert_eq!(agent.accrued_commission, 200);
+    assert_eq!(agent.commission_bps, 2_000);
+    settle_epoch(&mut config, &mut agent, 200);
+    assert_eq!(agent.accrued_commission, 400);
+    assert_eq!(agent.acc_reward_per_share, REWARD_PRECISION * (900 + 900 + 800) / 1_000);
+
+    // A decrease applies at once, and the cap always applies
+    agent.schedule_commission(500, 200, 50).unwrap();
+    assert_eq!(agent.commission_bps, 500);
+    config.max_commission_bps = 300;
+    settle_epoch(&mut config, &mut agent, 300);
+    assert_eq!(agent.accrued_commission, 430);
+}
+
+// Test lowering the commission cap clamps agents' stored commission, while raising it is
+// announced and does not restore an agent's old rate
+#[test]
+fn test_commission_cap_changes() {
+    use Eonium_ai::state::{AiAgent, PlatformConfig, REWARD_PRECISION};
+
+    let mut config = PlatformConfig { max_commission_bps: 2_000, commission_notice_period: 50, ..Default::default() };
+    let mut agent = AiAgent {
+        commission_bps: 2_000,
+        pending_commission_bps: 1_800,
+        commission_effective_at: 1_000,
+        weighted_stake: 1_000,
+        ..Default::default()
+    };
+
+    // Lowering applies at once and the clamp is kept, including on the announced increase
+    config.schedule_commission_cap(500, 100).unwrap();
+    assert_eq!(config.max_commission_bps, 500);
+    config.acc_reward_per_share += REWARD_PRECISION;
+    config.last_reward_timestamp = 100;
+    agent.settle_rewards(&config).unwrap();
+    assert_eq!(agent.accrued_commission, 50);
+    assert_eq!(agent.commission_bps, 500);
+    assert_eq!(agent.commission_effective_at, 0);
+
+    // Raising is announced and only promoted once the notice period has passed
+    config.schedule_commission_cap(3_000, 200).unwrap();
+    assert_eq!(config.max_commission_bps, 500);
+    config.apply_pending_commission_cap(249);
+    assert_eq!(config.max_commission_bps, 500);
+    config.apply_pending_commission_cap(250);
+    assert_eq!(config.max_commission_bps, 3_000);
+
+    // The agent keeps its clamped rate until it announces an increase itself
+    config.acc_reward_per_share += REWARD_PRECISION;
+    config.last_reward_timestamp = 300;
+    agent.settle_rewards(&config).unwrap();
+    assert_eq!(agent.accrued_commission, 100);
+    assert_eq!(agent.commission_bps, 500);
+}
+
+// Test agent metadata updates bump the version and keep a bounded hash history
+#[test]
+fn test_agent_metadata_history() {
+    use Eonium_ai::state::{AiAgent, MAX_METADATA_HISTORY, MAX_NAME_LENGTH};
+
+    let mut agent = AiAgent { name: "alpha".to_string(), ..Default::default() };
+    let first_hash = agent.metadata_hash();
+
+    agent.update_metadata("beta".to_string(), "second".to_string()).unwrap();
+    assert_eq!(agent.version, 1);
+    assert_eq!(agent.metadata_history, vec![first_hash]);
+
+    for i in 0..MAX_METADATA_HISTORY {
+        agent.update_metadata(format!("agent-{}", i), String::new()).unwrap();
+    }
+    assert_eq!(agent.metadata_history.len(), MAX_METADATA_HISTORY);
+    assert_ne!(agent.metadata_history[0], first_hash);
+
+    assert!(agent.update_metadata("x".repeat(MAX_NAME_LENGTH + 1), String::new()).is_err());
+}
+
+// Test agent lifecycle transitions and that retirement waits for the agent to drain
+#[test]
+fn test_agent_lifecycle() {
+    use Eonium_ai::state::{AgentStatus, AiAgent};
+
+    const UNBONDING: i64 = 100;
+    let mut agent = AiAgent::default();
+    assert_eq!(agent.status, AgentStatus::Pending);
+    assert!(agent.transition_to(AgentStatus::Paused, 0, UNBONDING).is_err());
+
+    agent.transition_to(AgentStatus::Active, 0, UNBONDING).unwrap();
+    agent.transition_to(AgentStatus::Paused, 10, UNBONDING).unwrap();
+    agent.transition_to(AgentStatus::Retiring, 20, UNBONDING).unwrap();
+    assert!(agent.transition_to(AgentStatus::Active, 30, UNBONDING).is_err());
+
+    // Still staked, then drained but inside the unbonding period
+    agent.staked_amount = 500;
+    assert!(agent.transition_to(AgentStatus::Retired, 200, UNBONDING).is_err());
+    agent.staked_amount = 0;
+    assert!(agent.transition_to(AgentStatus::Retired, 119, UNBONDING).is_err());
+    agent.transition_to(AgentStatus::Retired, 120, UNBONDING).unwrap();
+}
+
+// Test a drained agent stays open until every settled reward and commission has been claimed
+#[test]
+fn test_agent_close_waits_for_unclaimed_rewards() {
+    use Eonium_ai::state::{AiAgent, PlatformConfig, RewardSlot, StakePosition, BASE_MULTIPLIER_BPS, REWARD_PRECISION};
+
+    let mut config = PlatformConfig { max_commission_bps: 10_000, ..Default::default() };
+    config.reward_slots[0] = RewardSlot { mint: Pubkey::new_unique(), rate_per_epoch: 100, ..Default::default() };
+    let mut agent = AiAgent { commission_bps: 1_000, ..Default::default() };
+    let mut position = StakePosition { amount: 1_000, multiplier_bps: BASE_MULTIPLIER_BPS, ..Default::default() };
+    agent.settle_rewards(&config).unwrap();
+    let (old_weight, new_weight) = position.refresh_weight().unwrap();
+    config.apply_position_weight_change(&mut agent, old_weight, new_weight).unwrap();
+    assert!(!agent.has_unclaimed_rewards());
+
+    // The position unstakes everything after earning 1_000 and 100 in slot 0
+    config.acc_reward_per_share += REWARD_PRECISION;
+    config.reward_slots[0].acc_reward_per_share += REWARD_PRECISION / 10;
+    agent.settle_rewards(&config).unwrap();
+    agent.settle_position(&mut position).unwrap();
+    position.amount = 0;
+    let (old_weight, new_weight) = position.refresh_weight().unwrap();
+    config.apply_position_weight_change(&mut agent, old_weight, new_weight).unwrap();
+    assert_eq!((agent.unclaimed_rewards, agent.unclaimed_slot_rewards[0]), (900, 90));
+    assert_eq!((agent.accrued_commission, agent.accrued_slot_commission[0]), (100, 10));
+
+    // Each outstanding balance on its own keeps the agent open
+    agent.record_reward_claim(position.pending_rewards).unwrap();
+    agent.record_slot_reward_claim(0, position.take_slot_reward(0, u64::MAX)).unwrap();
+    assert!(agent.has_unclaimed_rewards());
+    agent.accrued_commission = 0;
+    assert!(agent.has_unclaimed_rewards());
+    agent.accrued_slot_commission[0] = 0;
+    assert!(!agent.has_unclaimed_rewards());
+    assert!(agent.record_reward_claim(1).is_err());
+}
+
+// Test agents get sequential IDs and an index entry pointing back at their account
+#[test]
+fn test_agent_ids_and_index() {
+    use Eonium_ai::state::{AgentIndex, PlatformConfig};
+
+    let mut config = PlatformConfig::default();
+    let owner = Pubkey::new_unique();
+    let ids: Vec<u64> = (0..3).map(|_| config.next_agent_id().unwrap()).collect();
+    assert_eq!(ids, vec![0, 1, 2]);
+    assert_eq!(config.agent_count, 3);
+
+    let program_id = Pubkey::new_unique();
+    let (agent, _) = Pubkey::find_program_address(&[b"ai-agent", owner.as_ref(), &ids[2].to_le_bytes()], &program_id);
+    let mut index = AgentIndex::default();
+    index.init(ids[2], agent, owner, 254);
+    assert_eq!((index.agent_id, index.agent, index.owner, index.bump), (2, agent, owner, 254));
+
+    // The counter never wraps around onto an existing ID
+    config.agent_count = u64::MAX;
+    assert!(config.next_agent_id().is_err());
+    assert_eq!(config.agent_count, u64::MAX);
+}
+
+// Test agent evolution ordering, model versioning and the minimum interval
+#[test]
+fn test_agent_evolution() {
+    use Eonium_ai::state::AiAgent;
+
+    const INTERVAL: i64 = 100;
+    let (behavior, model_a, model_b) = ([1u8; 32], [2u8; 32], [3u8; 32]);
+    let mut agent = AiAgent::default();
+
+    agent.evolve(behavior, model_a, 1, 1, 10, 50, INTERVAL).unwrap();
+    assert!(agent.evolve(behavior, model_a, 1, 2, 20, 149, INTERVAL).is_err());
+
+    // Chapters must be consecutive and a new model needs a higher version
+    assert!(agent.evolve(behavior, model_a, 1, 3, 20, 150, INTERVAL).is_err());
+    assert!(agent.evolve(behavior, model_b, 1, 2, 20, 150, INTERVAL).is_err());
+    agent.evolve(behavior, model_b, 2, 2, 20, 150, INTERVAL).unwrap();
+    assert_eq!((agent.chapter_count, agent.model_version, agent.last_evolution_timestamp), (2, 2, 150));
+}
+
+// Test typed agent metadata validation and that the account size tracks its content
+#[test]
+fn test_agent_metadata_schema() {
+    use Eonium_ai::state::{AgentMetadata, AgentMetadataParams, MAX_CAPABILITIES, MAX_LABEL_LENGTH};
+
+    let mut params = AgentMetadataParams {
+        capabilities: vec!["summarize".to_string()],
+        tags: vec!["nlp".to_string()],
+        model_family: "llama".to_string(),
+        manifest_hash: [7u8; 32],
+        ..Default::default()
+    };
+    params.validate().unwrap();
+    let small = AgentMetadata::space(&params);
+
+    params.capabilities.push("translate".to_string());
+    params.validate().unwrap();
+    assert_eq!(AgentMetadata::space(&params), small + 4 + "translate".len());
+
+    // Duplicates, oversized labels, too many entries and a missing manifest are rejected
+    params.capabilities.push("translate".to_string());
+    assert!(params.validate().is_err());
+    params.capabilities = vec!["x".repeat(MAX_LABEL_LENGTH + 1)];
+    assert!(params.validate().is_err());
+    params.capabilities = (0..=MAX_CAPABILITIES).map(|i| format!("cap-{}", i)).collect();
+    assert!(params.validate().is_err());
+    params.capabilities.clear();
+    params.manifest_hash = [0u8; 32];
+    assert!(params.validate().is_err());
+}
+
+// Test that an operator can run the agent without being able to control it
+#[test]
+fn test_agent_operator_key() {
+    use Eonium_ai::state::AiAgent;
+
+    let (owner, operator) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let mut agent = AiAgent { owner, ..Default::default() };
+    assert!(!agent.is_operated_by(&Pubkey::default(), None));
+    assert!(!agent.is_operated_by(&operator, None));
+
+    agent.operator = operator;
+    agent.operator_set_by = owner;
+    assert!(agent.is_operated_by(&operator, None));
+    assert!(agent.is_operated_by(&owner, None));
+    assert!(!agent.is_controlled_by(&operator, None));
+
+    // Revoking restores owner-only access
+    agent.operator = Pubkey::default();
+    assert!(!agent.is_operated_by(&operator, None));
+}
+
+// Test that selling a tokenized agent's NFT revokes the operator appointed by the seller
+#[test]
+fn test_agent_operator_lapses_on_nft_transfer() {
+    use Eonium_ai::state::AiAgent;
+
+    let (seller, buyer, operator, mint) =
+        (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+    let mut agent = AiAgent { owner: seller, ownership_mint: mint, ..Default::default() };
+    agent.operator = operator;
+    agent.operator_set_by = seller;
+
+    // The operator proves its appointer still holds the NFT
+    assert!(agent.is_operated_by(&operator, Some(&token_account(mint, seller, 1))));
+    assert!(!agent.is_operated_by(&operator, None));
+
+    // Once the NFT moves, neither the seller's emptied account nor the buyer's vouches for it
+    assert!(!agent.is_operated_by(&operator, Some(&token_account(mint, seller, 0))));
+    assert!(!agent.is_operated_by(&operator, Some(&token_account(mint, buyer, 1))));
+    assert!(agent.is_operated_by(&buyer, Some(&token_account(mint, buyer, 1))));
+
+    // The buyer re-appointing the same key restores it
+    agent.operator_set_by = buyer;
+    assert!(agent.is_operated_by(&operator, Some(&token_account(mint, buyer, 1))));
+}
+
+// Token account holding `amount` of `mint` for `owner`, as the token program stores it
+fn token_account(mint: Pubkey, owner: Pubkey, amount: u64) -> anchor_spl::token::TokenAccount {
+    use anchor_lang::solana_program::program_pack::Pack;
+
+    let mut data = vec![0u8; spl_token::state::Account::LEN];
+    spl_token::state::Account {
+        mint,
+        owner,
+        amount,
+        state: spl_token::state::AccountState::Initialized,
+        ..Default::default()
+    }
+    .pack_into_slice(&mut data);
+    anchor_spl::token::TokenAccount::try_deserialize(&mut data.as_slice()).unwrap()
+}
+
+// Test that once tokenized, only the holder of the ownership NFT controls the agent
+#[test]
+fn test_agent_nft_ownership() {
+    use Eonium_ai::agent_nft::{agent_nft_data, AGENT_NFT_SYMBOL, MAX_URI_LENGTH};
+    use Eonium_ai::state::AiAgent;
+
+    let (owner, buyer, mint) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+    let mut agent = AiAgent { owner, name: "alpha".to_string(), ..Default::default() };
+    assert!(agent.is_controlled_by(&owner, None));
+
+    agent.ownership_mint = mint;
+    let owner_nft = token_account(mint, owner, 1);
+    assert!(agent.is_controlled_by(&owner, Some(&owner_nft)));
+    assert!(!agent.is_controlled_by(&owner, None));
+
+    // After a sale the registering key loses control and the buyer gains it
+    let buyer_nft = token_account(mint, buyer, 1);
+    let emptied = token_account(mint, owner, 0);
+    assert!(agent.is_controlled_by(&buyer, Some(&buyer_nft)));
+    assert!(!agent.is_controlled_by(&owner, Some(&emptied)));
+    assert!(!agent.is_controlled_by(&owner, Some(&buyer_nft)));
+    assert!(!agent.is_controlled_by(&buyer, Some(&token_account(Pubkey::new_unique(), buyer, 1))));
+
+    // The NFT carries the agent's name, and over-long URIs are rejected
+    let data = agent_nft_data(&agent.name, "https://example.com/alpha.json".to_string()).unwrap();
+    assert_eq!((data.name.as_str(), data.symbol.as_str()), ("alpha", AGENT_NFT_SYMBOL));
+    assert_eq!(data.seller_fee_basis_points, 0);
+    assert!(agent_nft_data(&agent.name, "u".repeat(MAX_URI_LENGTH + 1)).is_err());
+}
+
+// Test the slash dispute window, appeal and batched application to positions
+#[test]
+fn test_slash_proposal_lifecycle() {
+    use Eonium_ai::slashing::{SlashProposal, SlashStatus};
+    use Eonium_ai::state::{AiAgent, StakePosition};
+
+    let mut agent = AiAgent { position_count: 2, slash_pending: true, ..Default::default() };
+    let mut proposal = SlashProposal::default();
+    proposal.init(Pubkey::new_unique(), 0, Pubkey::new_unique(), 1_000, [9u8; 32], 100, 50, 255).unwrap();
+    assert_eq!(proposal.dispute_deadline, 150);
+
+    // Appeals are only accepted inside the dispute window, and must be decided in time
+    let mut late = proposal.clone();
+    assert!(late.appeal(151, 40).is_err());
+    proposal.appeal(150, 40).unwrap();
+    assert_eq!((proposal.status, proposal.resolution_deadline), (SlashStatus::Appealed, 190));
+    assert!(proposal.can_resolve(190) && !proposal.can_resolve(191));
+
+    // Upheld: both open positions must be slashed before stake can move again
+    proposal.finalize(&mut agent).unwrap();
+    assert_eq!((proposal.status, proposal.positions_remaining, agent.slash_count), (SlashStatus::Applying, 2, 1));
+
+    let mut position = StakePosition { amount: 1_005, ..Default::default() };
+    assert_eq!(position.apply_slash(proposal.slash_bps, agent.slash_count).unwrap(), 100);
+    assert_eq!((position.amount, position.slash_count), (905, 1));
+
+    proposal.record_batch(&mut agent, 1, 100).unwrap();
+    assert!(agent.slash_pending);
+    proposal.record_batch(&mut agent, 1, 50).unwrap();
+    assert_eq!((proposal.status, proposal.slashed_amount), (SlashStatus::Completed, 150));
+    assert!(!agent.slash_pending);
+    assert!(proposal.record_batch(&mut agent, 1, 0).is_err());
+}
+
+// Test a finalized slash also cuts tokens still unbonding, and an undecided appeal lapses
+#[test]
+fn test_slash_reaches_unbonding_tickets() {
+    use Eonium_ai::slashing::{SlashProposal, SlashStatus};
+    use Eonium_ai::state::{AiAgent, UnbondingTicket};
+
+    // All stake has been unstaked, but two tickets are still open
+    let mut agent = AiAgent { unbonding_tickets: 2, slash_pending: true, ..Default::default() };
+    assert!(agent.has_slashable_stake());
+    assert!(!AiAgent::default().has_slashable_stake());
+    let mut proposal = SlashProposal::default();
+    proposal.init(Pubkey::new_unique(), 0, Pubkey::new_unique(), 1_000, [9u8; 32], 1_200, 50, 255).unwrap();
+    proposal.finalize(&mut agent).unwrap();
+    assert_eq!(proposal.positions_remaining, 2);
+
+    // A ticket still unbonding when the slash was proposed is cut like a position
+    let mut unbonding = UnbondingTicket { amount: 500, release_time: 1_250, ..Default::default() };
+    assert_eq!(unbonding.apply_slash(proposal.slash_bps, agent.slash_count, proposal.proposed_at).unwrap(), 50);
+    assert_eq!((unbonding.amount, unbonding.slash_count), (450, 1));
+    proposal.record_batch(&mut agent, 1, 50).unwrap();
+
+    // A ticket that had already matured only has to be visited
+    let mut matured = UnbondingTicket { amount: 500, release_time: 1_100, ..Default::default() };
+    assert_eq!(matured.apply_slash(proposal.slash_bps, agent.slash_count, proposal.proposed_at).unwrap(), 0);
+    assert_eq!((matured.amount, matured.slash_count), (500, 1));
+    proposal.record_batch(&mut agent, 1, 0).unwrap();
+    assert_eq!((proposal.status, proposal.slashed_amount), (SlashStatus::Completed, 50));
+    assert!(!agent.slash_pending);
+
+    // An appeal governance does not decide by the resolution deadline lapses
+    let mut appealed = SlashProposal::default();
+    appealed.init(Pubkey::new_unique(), 1, Pubkey::new_unique(), 1_000, [9u8; 32], 2_000, 50, 254).unwrap();
+    agent.slash_pending = true;
+    appealed.appeal(2_010, 100).unwrap();
+    assert!(appealed.can_resolve(2_110));
+    assert!(!appealed.can_resolve(2_111));
+    appealed.cancel(&mut agent);
+    assert_eq!(appealed.status, SlashStatus::Cancelled);
+    assert!(!agent.slash_pending);
+}
+
+// Test redelegation cooldown and that redelegated stake stays exposed to its source agent
+#[test]
+fn test_redelegation_exposure() {
+    use Eonium_ai::state::{AiAgent, StakePosition};
+
+    const COOLDOWN: i64 = 100;
+    let source = Pubkey::new_unique();
+    let mut position = StakePosition { amount: 1_000, ..Default::default() };
+    assert!(position.can_redelegate(10, COOLDOWN).unwrap());
+
+    position.amount += 2_000;
+    position.record_exposure(source, 2_000, 0, 500);
+    position.last_redelegated_at = 10;
+    assert!(!position.can_redelegate(109, COOLDOWN).unwrap());
+    assert!(position.can_redelegate(110, COOLDOWN).unwrap());
+    assert_eq!(position.unexposed_amount(), 1_000);
+
+    // A source slash proposed inside the window only cuts the exposed part
+    assert_eq!(position.apply_exposure_slash(1_000, 1, 499).unwrap(), 200);
+    assert_eq!((position.amount, position.exposed_amount, position.exposure_slash_count), (2_800, 1_800, 1));
+    assert_eq!(position.apply_exposure_slash(1_000, 2, 500).unwrap(), 0);
+
+    // A slash on the position's own agent never leaves more exposed than held
+    position.apply_slash(5_000, 1).unwrap();
+    assert_eq!((position.amount, position.exposed_amount, position.unexposed_amount()), (1_400, 1_400, 0));
+
+    position.clear_exposure();
+    assert!(!position.has_exposure());
+    assert_eq!(position.unexposed_amount(), 1_400);
+
+    // A source agent whose stake has all been redelegated away can still be slashed
+    let drained = AiAgent { outgoing_exposures: 1, ..Default::default() };
+    assert!(drained.staked_amount == 0 && drained.has_slashable_stake());
+}
+
+// Test stake warmup, the per-epoch activation cap and the unbonding cooldown
+#[test]
+fn test_stake_warmup_and_cooldown() {
+    use Eonium_ai::state::{PlatformConfig, StakePosition, BASE_MULTIPLIER_BPS};
+
+    let mut config = PlatformConfig {
+        epoch_duration: 100,
+        epoch_origin: 1_000,
+        unbonding_period: 10,
+        warmup_epochs: 1,
+        cooldown_epochs: 2,
+        activation_cap_bps: 2_500,
+        total_staked: 2_000,
+        ..Default::default()
+    };
+    let mut position = StakePosition { multiplier_bps: BASE_MULTIPLIER_BPS, ..Default::default() };
+    let mut other = StakePosition { multiplier_bps: BASE_MULTIPLIER_BPS, ..Default::default() };
+
+    // Epochs count from the emission schedule's start
+    assert_eq!((config.current_epoch(999), config.current_epoch(1_050), config.current_epoch(1_150)), (0, 0, 1));
+
+    // Deposited in epoch 0, so nothing activates before epoch 1
+    for (stake, amount) in [(&mut position, 1_200), (&mut other, 800)] {
+        stake.add_activating(amount, config.current_epoch(1_050) + config.warmup_epochs).unwrap();
+        config.sync_activating(0, amount).unwrap();
+    }
+    config.activate_stake(&mut position, 1_050).unwrap();
+    assert_eq!((position.active_amount(), position.vote_weight(1_050)), (0, 0));
+
+    // Each epoch a quarter of total stake activates, shared pro rata whoever comes first
+    config.activate_stake(&mut other, 1_150).unwrap();
+    config.activate_stake(&mut position, 1_150).unwrap();
+    config.activate_stake(&mut position, 1_160).unwrap();
+    assert_eq!((other.active_amount(), other.activating_amount), (200, 600));
+    assert_eq!((position.active_amount(), position.activating_amount), (300, 900));
+    assert_eq!((config.total_activating, config.activation_budget), (1_500, 0));
+    position.refresh_weight().unwrap();
+    assert_eq!(position.weighted_amount, 300);
+
+    // Unstaking takes stake that is still activating first
+    position.remove_stake(300).unwrap();
+    config.sync_activating(900, position.activating_amount).unwrap();
+    assert_eq!((position.active_amount(), position.activating_amount), (300, 600));
+
+    // The next epoch's budget is shared over what is still activating then
+    config.activate_stake(&mut position, 1_250).unwrap();
+    assert_eq!(position.vote_weight(1_250), 550);
+
+    // Exits deactivate in order, at most a quarter of total stake per epoch, then cool down
+    assert_eq!(config.schedule_unbonding(300, 1_150).unwrap(), 1_300);
+    assert_eq!(config.schedule_unbonding(400, 1_150).unwrap(), 1_400);
+    assert_eq!((config.deactivation_epoch, config.deactivation_queued), (2, 200));
+    assert_eq!(config.schedule_unbonding(100, 1_550).unwrap(), 1_700);
+
+    // Without a cap or cooldown only the unbonding period applies
+    config.cooldown_epochs = 0;
+    config.activation_cap_bps = 0;
+    assert_eq!(config.schedule_unbonding(2_000, 1_150).unwrap(), 1_160);
+}
+
+// Test changing the activation config mid-epoch rebuilds the epoch's activation budget and the
+// deactivation queue under the new cap
+#[test]
+fn test_activation_config_change_mid_epoch() {
+    use Eonium_ai::state::{PlatformConfig, StakePosition, BASE_MULTIPLIER_BPS};
+
+    let mut config = PlatformConfig {
+        epoch_duration: 100,
+        activation_cap_bps: 1_000,
+        total_staked: 10_000,
+        ..Default::default()
+    };
+    let mut position = StakePosition { multiplier_bps: BASE_MULTIPLIER_BPS, ..Default::default() };
+    let mut other = StakePosition { multiplier_bps: BASE_MULTIPLIER_BPS, ..Default::default() };
+    for stake in [&mut position, &mut other] {
+        stake.add_activating(4_000, 0).unwrap();
+        config.sync_activating(0, 4_000).unwrap();
+    }
+
+    // Under the old cap 1_000 activates this epoch and a 3_000 exit takes three epochs
+    config.activate_stake(&mut position, 50).unwrap();
+    assert_eq!((position.active_amount(), config.activation_budget), (500, 500));
+    assert_eq!(config.schedule_unbonding(3_000, 50).unwrap(), 200);
+
+    // Doubling the cap mid-epoch opens a fresh budget over what is still activating
+    config.set_activation_config(0, 0, 2_000);
+    assert_eq!((config.activation_budget_epoch, config.deactivation_epoch, config.deactivation_queued), (None, 0, 0));
+    config.activate_stake(&mut other, 60).unwrap();
+    config.activate_stake(&mut position, 70).unwrap();
+    assert_eq!((other.active_amount(), position.active_amount()), (1_067, 500));
+    assert_eq!(config.activation_budget, 2_000 - 1_067);
+
+    // A new exit is queued under the new cap rather than behind the old queue
+    assert_eq!(config.schedule_unbonding(500, 60).unwrap(), 60);
+    assert_eq!((config.deactivation_epoch, config.deactivation_queued), (0, 500));
+}
+
+// Test an unbonding ticket only matures once the unbonding period has passed
+#[test]
+fn test_unbonding_ticket_lifecycle() {
+    use Eonium_ai::state::{PlatformConfig, UnbondingTicket};
+
+    let mut config = PlatformConfig { epoch_duration: 100, unbonding_period: 250, ..Default::default() };
+    let user = Pubkey::new_unique();
+
+    // Unstaked at t = 1_000, so the tokens are withdrawable from t = 1_250
+    let release_time = config.schedule_unbonding(500, 1_000).unwrap();
+    assert_eq!(release_time, 1_250);
+    let mut ticket = UnbondingTicket::default();
+    ticket.init(user, Pubkey::new_unique(), 7, 0, 500, 1_000, release_time, 0, 254);
+    assert_eq!((ticket.user, ticket.agent_id, ticket.ticket_id, ticket.amount), (user, 7, 0, 500));
+
+    assert!(!ticket.is_mature(1_000));
+    assert!(!ticket.is_mature(1_249));
+    assert!(ticket.is_mature(1_250));
+
+    // A later ticket of the same user gets its own ID and release time
+    let mut next = UnbondingTicket::default();
+    next.init(user, ticket.agent, 7, 1, 200, 1_100, config.schedule_unbonding(200, 1_100).unwrap(), 0, 253);
+    assert!(ticket.is_mature(1_300) && !next.is_mature(1_300));
+    assert!(next.is_mature(1_350));
+}
+
+// Test a user's positions on two agents are accounted for separately
+#[test]
+fn test_stake_positions_per_agent() {
+    use Eonium_ai::state::{
+        AiAgent, EmissionCurve, EmissionSchedule, PlatformConfig, StakePosition, BASE_MULTIPLIER_BPS,
+    };
+
+    let mut config = PlatformConfig { epoch_duration: 100, stake_weight_bps: 10_000, ..Default::default() };
+    let mut schedule = EmissionSchedule {
+        curve: EmissionCurve::Constant { rate: 400 },
+        supply_cap: u64::MAX,
+        ..Default::default()
+    };
+    let user = Pubkey::new_unique();
+    let (mut first, mut second) = (AiAgent::default(), AiAgent::default());
+    let mut on_first = StakePosition::default();
+    let mut on_second = StakePosition::default();
+    on_first.init(user, Pubkey::new_unique(), 0, 0, 255);
+    on_second.init(user, Pubkey::new_unique(), 1, 0, 255);
+
+    // 1_000 on the first agent and 3_000 on the second
+    for (agent, position, amount) in [(&mut first, &mut on_first, 1_000), (&mut second, &mut on_second, 3_000)] {
+        position.add_activating(amount, 0).unwrap();
+        config.sync_activating(0, amount).unwrap();
+        config.activate_stake(position, 0).unwrap();
+        config.total_staked += amount;
+        let (old_weight, new_weight) = position.refresh_weight().unwrap();
+        config.apply_position_weight_change(agent, old_weight, new_weight).unwrap();
+        position.reset_reward_debt(agent.acc_reward_per_share).unwrap();
+    }
+    assert_eq!((first.weighted_stake, second.weighted_stake, config.total_weighted_stake), (1_000, 3_000, 4_000));
+
+    // One epoch later each position has earned in proportion to its own amount
+    config.accrue_rewards(&mut schedule, 100).unwrap();
+    for (agent, position) in [(&mut first, &mut on_first), (&mut second, &mut on_second)] {
+        agent.settle_rewards(&config).unwrap();
+        position.settle_rewards(agent.acc_reward_per_share).unwrap();
+    }
+    assert_eq!((on_first.pending_rewards, on_second.pending_rewards), (100, 300));
+
+    // Removing stake from one position leaves the other untouched
+    on_second.remove_stake(3_000).unwrap();
+    let (old_weight, new_weight) = on_second.refresh_weight().unwrap();
+    config.apply_position_weight_change(&mut second, old_weight, new_weight).unwrap();
+    assert_eq!((on_first.amount, on_second.amount), (1_000, 0));
+    assert_eq!((second.weighted_stake, config.total_weighted_stake), (0, 1_000));
+}
+
+// Test delegators pay the owner's commission on their rewards, in the primary reward and in reward slots
+#[test]
+fn test_delegation_with_owner_commission() {
+    use Eonium_ai::state::{AiAgent, PlatformConfig, RewardSlot, StakePosition, BASE_MULTIPLIER_BPS, REWARD_PRECISION};
+
+    let (owner, delegator) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let mut config = PlatformConfig { max_commission_bps: 10_000, ..Default::default() };
+    config.reward_slots[0] = RewardSlot { mint: Pubkey::new_unique(), rate_per_epoch: 100, ..Default::default() };
+    let mut agent = AiAgent::default();
+    agent.init(0, owner, "alpha".to_string(), String::new(), 1_500, 0, 255);
+
+    let mut owned = StakePosition { user: owner, multiplier_bps: BASE_MULTIPLIER_BPS, ..Default::default() };
+    let mut delegated = StakePosition { user: delegator, multiplier_bps: BASE_MULTIPLIER_BPS, ..Default::default() };
+    agent.settle_rewards(&config).unwrap();
+    for (position, amount) in [(&mut owned, 1_000), (&mut delegated, 3_000)] {
+        position.amount = amount;
+        let (old_weight, new_weight) = position.refresh_weight().unwrap();
+        config.apply_position_weight_change(&mut agent, old_weight, new_weight).unwrap();
+    }
+
+    // 4_000 of primary rewards and 400 of slot rewards reach the agent
+    config.acc_reward_per_share += REWARD_PRECISION;
+    config.reward_slots[0].acc_reward_per_share += REWARD_PRECISION / 10;
+    agent.settle_rewards(&config).unwrap();
+    assert_eq!(agent.accrued_commission, 600);
+    assert_eq!(agent.accrued_slot_commission[0], 60);
+
+    for position in [&mut owned, &mut delegated] {
+        position.settle_rewards(agent.acc_reward_per_share).unwrap();
+        position.settle_slot_rewards(&agent.slot_acc_per_share).unwrap();
+    }
+    assert_eq!((owned.pending_rewards, delegated.pending_rewards), (850, 2_550));
+    assert_eq!((owned.slot_pending_rewards[0], delegated.slot_pending_rewards[0]), (85, 255));
+}
+
 // Helper function to derive stake account PDA (adjust based on program logic)
 fn derive_stake_account_pda(user: &Pubkey, agent: &Pubkey, program_id: &Pubkey) -> Pubkey {
     let (pda, _bump) = Pubkey::find_program_address(